@@ -0,0 +1,140 @@
+use crate::config::AppEntry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Freedesktop main categories and the Apps view group each goes in. An
+/// entry goes in the group of the first of its categories listed here.
+const CATEGORY_GROUPS: &[(&str, &str)] = &[
+    ("Development", "Development"),
+    ("Game", "Games"),
+    ("Graphics", "Graphics"),
+    ("AudioVideo", "Media"),
+    ("Audio", "Media"),
+    ("Video", "Media"),
+    ("Network", "Internet"),
+    ("Office", "Office"),
+    ("Education", "Education"),
+    ("Science", "Education"),
+    ("Settings", "System"),
+    ("System", "System"),
+    ("Utility", "Utilities"),
+];
+
+/// `applications/` in the user's and the system's XDG data directories,
+/// the user's first
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(std::env::split_paths(&system));
+    dirs.into_iter().map(|dir| dir.join("applications")).collect()
+}
+
+/// Apps from the `.desktop` files of installed applications, sorted by
+/// name. A user's file hides a system one with the same file name.
+pub fn discover_apps() -> Vec<AppEntry> {
+    let mut by_id = BTreeMap::new();
+    for dir in application_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let id = entry.file_name().to_string_lossy().into_owned();
+            if !id.ends_with(".desktop") || by_id.contains_key(&id) {
+                continue;
+            }
+            // Hidden entries still hide system ones, so they're kept as None
+            let app = fs::read_to_string(&path).ok().and_then(|content| parse_desktop_entry(&content));
+            by_id.insert(id, app);
+        }
+    }
+    let mut apps: Vec<AppEntry> = by_id.into_values().flatten().collect();
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+/// The app described by a `.desktop` file, unless it's hidden or not an
+/// application
+pub fn parse_desktop_entry(content: &str) -> Option<AppEntry> {
+    let mut fields = BTreeMap::new();
+    let mut in_entry = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if let (true, Some((key, value))) = (in_entry, line.split_once('=')) {
+            // Localised keys such as Name[de] are keys of their own and go unused
+            fields.entry(key.trim()).or_insert(value.trim());
+        }
+    }
+
+    let flag = |key: &str| fields.get(key).is_some_and(|v| *v == "true");
+    if fields.get("Type") != Some(&"Application") || flag("NoDisplay") || flag("Hidden") {
+        return None;
+    }
+    let mut words = crate::launcher::split_command(&exec_without_field_codes(fields.get("Exec")?)).into_iter();
+    let command = words.next()?;
+    let args: Vec<String> = words.collect();
+    Some(AppEntry {
+        name: fields.get("Name")?.to_string(),
+        command,
+        args: (!args.is_empty()).then_some(args),
+        group: fields.get("Categories").and_then(|c| group_for_categories(c)).map(str::to_string),
+        favourite: false,
+    })
+}
+
+/// The group for a `Categories=` value such as `GTK;Network;WebBrowser;`
+pub fn group_for_categories(categories: &str) -> Option<&'static str> {
+    let categories: Vec<&str> = categories.split(';').map(str::trim).collect();
+    CATEGORY_GROUPS
+        .iter()
+        .find(|(category, _)| categories.contains(category))
+        .map(|(_, group)| *group)
+}
+
+/// `Exec=` with the `%f`, `%U` and similar codes for files to open taken
+/// out, since apps are launched without any; `%%` is a literal `%`
+fn exec_without_field_codes(exec: &str) -> String {
+    let mut out = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+        } else if let Some('%') = chars.next() {
+            out.push('%');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let content = "[Desktop Entry]\nType=Application\nName=Firefox\nName[de]=Feuerfuchs\n\
+                       Exec=firefox --new-window %u\nCategories=GNOME;GTK;Network;WebBrowser;\n\
+                       [Desktop Action private]\nName=Private window\nExec=firefox --private-window %u\n";
+        let app = parse_desktop_entry(content).unwrap();
+        assert_eq!(app.name, "Firefox");
+        assert_eq!(app.command, "firefox");
+        assert_eq!(app.args, Some(vec!["--new-window".to_string()]));
+        assert_eq!(app.group.as_deref(), Some("Internet"));
+
+        assert!(parse_desktop_entry("[Desktop Entry]\nType=Application\nName=X\nExec=x\nNoDisplay=true\n").is_none());
+        assert!(parse_desktop_entry("[Desktop Entry]\nType=Link\nName=X\nURL=https://example.com\n").is_none());
+    }
+
+    #[test]
+    fn test_group_for_categories() {
+        assert_eq!(group_for_categories("Development;IDE;Utility;"), Some("Development"));
+        assert_eq!(group_for_categories("AudioVideo;Player;"), Some("Media"));
+        assert_eq!(group_for_categories("X-Custom;"), None);
+        assert_eq!(exec_without_field_codes("app --rate 100%% %F"), "app --rate 100% ");
+    }
+}
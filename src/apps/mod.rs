@@ -0,0 +1,158 @@
+use crate::config::{AppEntry, Config};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod desktop;
+
+pub use desktop::discover_apps;
+
+/// Tab showing every app, whatever its group
+pub const ALL_GROUP: &str = "All";
+
+/// How many times each app has been launched, by name, kept in
+/// `app_usage.json` in the config directory
+pub struct AppUsage {
+    data_path: PathBuf,
+    counts: BTreeMap<String, u64>,
+}
+
+impl AppUsage {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Config::config_dir()?.join("app_usage.json")))
+    }
+
+    fn with_path(data_path: PathBuf) -> Self {
+        // Counts only affect ordering, so an unreadable file starts afresh
+        let counts = read_counts(&data_path).unwrap_or_default();
+        Self { data_path, counts }
+    }
+
+    pub fn count(&self, name: &str) -> u64 {
+        self.counts.get(name).copied().unwrap_or(0)
+    }
+
+    /// Count a launch of `name`, adding to what other instances have saved
+    pub fn record(&mut self, name: &str) -> Result<()> {
+        let path = &self.data_path;
+        self.counts = crate::persist::with_lock(path, || {
+            let mut counts = read_counts(path).unwrap_or_default();
+            *counts.entry(name.to_string()).or_insert(0) += 1;
+            let json = serde_json::to_string_pretty(&counts).context("Failed to serialize app usage")?;
+            crate::persist::write_atomic(path, json.as_bytes())?;
+            Ok(counts)
+        })?;
+        Ok(())
+    }
+}
+
+fn read_counts(path: &Path) -> Option<BTreeMap<String, u64>> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// The Apps view's tabs and the apps shown in the current one
+pub struct AppList {
+    /// `ALL_GROUP`, then each app group in config order
+    pub groups: Vec<String>,
+    pub group: usize,
+    /// Indices into the configured apps, in display order
+    pub visible: Vec<usize>,
+    pub selected: usize,
+}
+
+impl AppList {
+    pub fn new(apps: &[AppEntry], usage: &AppUsage) -> Self {
+        let mut list = Self {
+            groups: Vec::new(),
+            group: 0,
+            visible: Vec::new(),
+            selected: 0,
+        };
+        list.refresh(apps, usage);
+        list
+    }
+
+    /// Rebuild the tabs and re-sort the current one, starting at the top
+    /// where the favourites and most used apps are
+    pub fn refresh(&mut self, apps: &[AppEntry], usage: &AppUsage) {
+        let current = self.groups.get(self.group).cloned();
+        self.groups = vec![ALL_GROUP.to_string()];
+        for group in apps.iter().filter_map(|app| app.group.as_ref()) {
+            if !self.groups.contains(group) {
+                self.groups.push(group.clone());
+            }
+        }
+        self.group = current
+            .and_then(|name| self.groups.iter().position(|g| *g == name))
+            .unwrap_or(0);
+        self.visible = ordered(apps, &self.groups[self.group], usage);
+        self.selected = 0;
+    }
+
+    /// Move `delta` tabs, wrapping around
+    pub fn switch_group(&mut self, delta: isize, apps: &[AppEntry], usage: &AppUsage) {
+        let count = self.groups.len() as isize;
+        self.group = (self.group as isize + delta).rem_euclid(count) as usize;
+        self.refresh(apps, usage);
+    }
+
+    pub fn selected_app<'a>(&self, apps: &'a [AppEntry]) -> Option<&'a AppEntry> {
+        self.visible.get(self.selected).and_then(|&i| apps.get(i))
+    }
+}
+
+/// Indices of the apps in `group`: favourites first, then by launch count,
+/// otherwise in config order
+pub fn ordered(apps: &[AppEntry], group: &str, usage: &AppUsage) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..apps.len())
+        .filter(|&i| group == ALL_GROUP || apps[i].group.as_deref() == Some(group))
+        .collect();
+    // The sort is stable, so ties keep config order
+    indices.sort_by_key(|&i| (!apps[i].favourite, std::cmp::Reverse(usage.count(&apps[i].name))));
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn app(name: &str, group: Option<&str>, favourite: bool) -> AppEntry {
+        AppEntry {
+            name: name.to_string(),
+            command: name.to_lowercase(),
+            group: group.map(str::to_string),
+            favourite,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_groups_and_order() {
+        let dir = TempDir::new("usage");
+        let mut usage = AppUsage::with_path(dir.join("app_usage.json"));
+        usage.record("Firefox").unwrap();
+        usage.record("Terminal").unwrap();
+        usage.record("Terminal").unwrap();
+        let reloaded = AppUsage::with_path(dir.join("app_usage.json")).count("Terminal");
+        assert_eq!(reloaded, 2);
+
+        let apps = [
+            app("Firefox", Some("Internet"), false),
+            app("Terminal", Some("System"), false),
+            app("Notes", None, true),
+            app("Mail", Some("Internet"), false),
+        ];
+        let mut list = AppList::new(&apps, &usage);
+        assert_eq!(list.groups, ["All", "Internet", "System"]);
+        assert_eq!(list.visible, [2, 1, 0, 3]);
+
+        list.switch_group(1, &apps, &usage);
+        assert_eq!(list.visible, [0, 3]);
+        list.switch_group(-2, &apps, &usage);
+        assert_eq!(list.groups[list.group], "System");
+        assert_eq!(list.selected_app(&apps).unwrap().name, "Terminal");
+    }
+}
@@ -0,0 +1,261 @@
+use crate::ascii_globe::camera::{lerp_rgb, PALETTE};
+use crate::ascii_globe::texture::load_texture;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Texture manifest describing a renderable body, e.g. `textures/earth.toml`:
+///
+/// ```toml
+/// name = "Earth"
+/// day = "earth.txt"
+/// night = "earth_night.txt"   # optional
+/// clouds = "earth_clouds.txt" # optional
+/// palette = " .:;',wiogOLXHWYV@" # optional, defaults to camera::PALETTE
+/// day_ramp = [[10, 30, 90], [40, 120, 60]] # optional colours, darkest first
+/// night_ramp = [[0, 0, 0], [255, 200, 90]] # optional, defaults to day_ramp
+/// ```
+///
+/// Texture paths are resolved relative to the manifest's directory.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BodyManifest {
+    pub name: String,
+    pub day: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub night: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clouds: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub palette: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_ramp: Option<Vec<[u8; 3]>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub night_ramp: Option<Vec<[u8; 3]>>,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl BodyManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read texture manifest {:?}", path))?;
+        let mut manifest: BodyManifest = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse texture manifest {:?}", path))?;
+        manifest.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(manifest)
+    }
+
+    /// Manifest for a body with only a day texture, both relative to `dir`
+    pub fn new(name: &str, day: PathBuf, dir: &Path) -> Self {
+        Self {
+            name: name.to_string(),
+            day,
+            night: None,
+            clouds: None,
+            palette: None,
+            day_ramp: None,
+            night_ramp: None,
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Write the manifest to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .with_context(|| format!("Failed to serialize texture manifest for {}", self.name))?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write texture manifest {:?}", path))
+    }
+
+    /// Manifest for texture directories that only contain `earth.txt` and
+    /// `earth_night.txt`, as used before manifests existed
+    fn legacy_earth(dir: &Path) -> Self {
+        Self {
+            night: Some(PathBuf::from("earth_night.txt")),
+            ..Self::new("Earth", PathBuf::from("earth.txt"), dir)
+        }
+    }
+
+    /// Case-insensitive lookup key, so `body = "moon"` matches `name = "Moon"`
+    pub fn key(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        self.dir.join(path)
+    }
+}
+
+/// Loaded textures for a single body
+pub struct Body {
+    pub name: String,
+    pub day: Vec<Vec<char>>,
+    pub night: Option<Vec<Vec<char>>>,
+    pub clouds: Option<Vec<Vec<char>>>,
+    pub palette: Vec<char>,
+    /// Colours spread evenly across the palette, darkest first, used by the
+    /// colour render modes
+    pub day_ramp: Vec<[u8; 3]>,
+    pub night_ramp: Vec<[u8; 3]>,
+}
+
+// Plain greyscale for bodies that don't define a ramp
+const GREY_RAMP: [[u8; 3]; 2] = [[40, 40, 40], [255, 255, 255]];
+
+impl Body {
+    pub fn load(manifest: &BodyManifest) -> Result<Self> {
+        let palette: Vec<char> = manifest
+            .palette
+            .as_deref()
+            .unwrap_or(PALETTE)
+            .chars()
+            .collect();
+        if palette.len() < 2 {
+            anyhow::bail!("Palette for {} needs at least two characters", manifest.name);
+        }
+        
+        let load = |path: &Path| -> Result<Vec<Vec<char>>> {
+            let path = manifest.resolve(path);
+            load_texture(&path, &palette)
+                .with_context(|| format!("Failed to load {:?} for {}", path, manifest.name))
+        };
+        
+        let day = load(&manifest.day)?;
+        let size = |texture: &[Vec<char>]| (texture[0].len(), texture.len());
+        // Night and cloud layers are sampled at the day texture's coordinates
+        let load_layer = |path: &Path| -> Result<Vec<Vec<char>>> {
+            let layer = load(path)?;
+            let ((width, height), (day_width, day_height)) = (size(&layer), size(&day));
+            if (width, height) != (day_width, day_height) {
+                anyhow::bail!(
+                    "{:?} for {} is {}x{} but the day texture is {}x{}",
+                    manifest.resolve(path),
+                    manifest.name,
+                    width,
+                    height,
+                    day_width,
+                    day_height
+                );
+            }
+            Ok(layer)
+        };
+        let night = manifest.night.as_deref().map(load_layer).transpose()?;
+        let clouds = manifest.clouds.as_deref().map(load_layer).transpose()?;
+        let day_ramp = manifest
+            .day_ramp
+            .clone()
+            .filter(|r| !r.is_empty())
+            .unwrap_or_else(|| GREY_RAMP.to_vec());
+        let night_ramp = manifest
+            .night_ramp
+            .clone()
+            .filter(|r| !r.is_empty())
+            .unwrap_or_else(|| day_ramp.clone());
+
+        Ok(Self {
+            name: manifest.name.clone(),
+            day,
+            night,
+            clouds,
+            palette,
+            day_ramp,
+            night_ramp,
+        })
+    }
+
+    /// Day-side colour for a brightness in [0, 1]
+    pub fn day_color(&self, level: f64) -> [u8; 3] {
+        ramp_color(&self.day_ramp, level)
+    }
+
+    /// Night-side colour for a brightness in [0, 1]
+    pub fn night_color(&self, level: f64) -> [u8; 3] {
+        ramp_color(&self.night_ramp, level)
+    }
+
+    /// Position of `c` in this body's palette
+    pub fn palette_index(&self, c: char) -> Option<usize> {
+        self.palette.iter().position(|&p| p == c)
+    }
+}
+
+fn ramp_color(ramp: &[[u8; 3]], level: f64) -> [u8; 3] {
+    if ramp.len() == 1 {
+        return ramp[0];
+    }
+    let position = level.clamp(0.0, 1.0) * (ramp.len() - 1) as f64;
+    let lower = (position.floor() as usize).min(ramp.len() - 2);
+    lerp_rgb(ramp[lower], ramp[lower + 1], position - lower as f64)
+}
+
+/// Directories searched for texture manifests, lowest priority first: the
+/// configured `globe.texture_path`, the bundled `textures` directory and
+/// `Config::config_dir()/textures`
+pub fn texture_dirs(configured: &str) -> Vec<PathBuf> {
+    let mut candidates = vec![PathBuf::from(configured), PathBuf::from("textures")];
+    if let Ok(config_dir) = Config::config_dir() {
+        candidates.push(config_dir.join("textures"));
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !candidate.is_dir() {
+            continue;
+        }
+        let canonical = candidate.canonicalize().unwrap_or_else(|_| candidate.clone());
+        if !dirs.iter().any(|d| d.canonicalize().unwrap_or_else(|_| d.clone()) == canonical) {
+            dirs.push(candidate);
+        }
+    }
+    dirs
+}
+
+/// Find every body in `dirs`. A manifest in a later directory replaces one
+/// with the same name from an earlier directory, so user textures in the
+/// config dir can override the bundled ones.
+pub fn discover(dirs: &[PathBuf]) -> Vec<BodyManifest> {
+    let mut bodies: Vec<BodyManifest> = Vec::new();
+
+    for dir in dirs {
+        let mut found = Vec::new();
+        if let Ok(entries) = fs::read_dir(dir) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("toml"))
+                .collect();
+            paths.sort();
+            // Skip manifests that fail to parse rather than hiding every body
+            found.extend(paths.iter().filter_map(|p| BodyManifest::load(p).ok()));
+        }
+
+        if !found.iter().any(|m| m.key() == "earth") && dir.join("earth.txt").exists() {
+            found.insert(0, BodyManifest::legacy_earth(dir));
+        }
+
+        for manifest in found {
+            match bodies.iter_mut().find(|b| b.key() == manifest.key()) {
+                Some(existing) => *existing = manifest,
+                None => bodies.push(manifest),
+            }
+        }
+    }
+
+    // Earth first, everything else in discovery order
+    if let Some(pos) = bodies.iter().position(|b| b.key() == "earth") {
+        let earth = bodies.remove(pos);
+        bodies.insert(0, earth);
+    }
+    bodies
+}
+
+/// Load the body called `name` from `dirs`
+pub fn find(dirs: &[PathBuf], name: &str) -> Result<Body> {
+    let key = name.to_lowercase();
+    let manifest = discover(dirs)
+        .into_iter()
+        .find(|m| m.key() == key)
+        .ok_or_else(|| anyhow::anyhow!("No textures found for body '{}' in {:?}", name, dirs))?;
+    Body::load(&manifest)
+}
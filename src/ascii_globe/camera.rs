@@ -1,7 +1,52 @@
+use crate::ascii_globe::body::Body;
 use crate::ascii_globe::math::{self, Vec3, clamp, clamp_int};
 
 pub const PALETTE: &str = " .:;',wiogOLXHWYV@";
 
+/// Per-frame inputs to `shade`
+pub struct SphereParams {
+    pub angle_offset: f64,
+    pub cloud_offset: f64,
+    /// Unit vector pointing towards the light, or `None` to render fully lit
+    pub light: Option<Vec3>,
+    /// Whether `Shade::rgb` should be computed
+    pub color: bool,
+}
+
+/// Where a ray meets the sphere. Nothing here depends on the globe's
+/// rotation or lighting, so hits can be reused between frames.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    /// World-space surface normal
+    pub normal: Vec3,
+    /// Texture coordinates before rotation: `u` runs once round the
+    /// equator, `v` from the north pole (0) to the south pole (1)
+    pub u: f64,
+    pub v: f64,
+}
+
+/// Result of shading a single ray
+pub struct Shade {
+    /// Palette character for the blended surface
+    pub ch: char,
+    /// Brightness of the blended surface, 0.0 (dark) to 1.0 (brightest)
+    pub level: f64,
+    /// Surface colour; black unless `SphereParams::color` is set
+    pub rgb: [u8; 3],
+}
+
+/// One terminal cell of rendered globe output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Option<[u8; 3]>,
+    pub bg: Option<[u8; 3]>,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell { ch: ' ', fg: None, bg: None };
+}
+
 pub struct Camera {
     pub x: f64,
     pub y: f64,
@@ -30,108 +75,155 @@ impl Camera {
         Self { x, y, z, matrix }
     }
     
-    pub fn render_sphere(
+    /// Project world-space `point` onto a `grid_width` x `grid_height` grid,
+    /// the inverse of the ray set-up in `intersect`. Returns `None` for points
+    /// behind the camera.
+    pub fn project(&self, point: Vec3, grid_width: usize, grid_height: usize) -> Option<(f64, f64)> {
+        let d = math::vector(point, [self.x, self.y, self.z]);
+        let m = &self.matrix;
+        let local_x = d[0] * m[0] + d[1] * m[1] + d[2] * m[2];
+        let local_y = d[0] * m[4] + d[1] * m[5] + d[2] * m[6];
+        let local_z = d[0] * m[8] + d[1] * m[9] + d[2] * m[10];
+        if local_z >= 0.0 {
+            return None;
+        }
+        
+        let ux = local_x / -local_z;
+        let uy = local_y / -local_z;
+        let half_width = grid_width as f64 / 2.0;
+        let half_height = grid_height as f64 / 2.0;
+        Some((half_width - ux * half_width / 1.2, half_height + uy * half_height))
+    }
+    
+    /// Whether `point` on a sphere centred at the origin faces the camera
+    pub fn faces(&self, point: Vec3) -> bool {
+        math::dot(point, math::vector([self.x, self.y, self.z], point)) > 0.0
+    }
+    
+    /// Cast a ray through point (`px`, `py`) of a `grid_width` x
+    /// `grid_height` sample grid at a sphere of `radius` whose axis is tilted
+    /// by `tilt` degrees. Coordinates are continuous, so a cell's centre is
+    /// `(x + 0.5, y + 0.5)`.
+    pub fn intersect(
         &self,
-        canvas: &mut [Vec<char>],
+        px: f64,
+        py: f64,
+        grid_width: usize,
+        grid_height: usize,
         radius: f64,
-        angle_offset: f64,
-        earth: &[Vec<char>],
-        earth_night: &[Vec<char>],
-        scale: f64,
         tilt: f64,
-        lighting: bool,
-        canvas_width: usize,
-        canvas_height: usize,
-    ) {
-        let light: Vec3 = [0.0, 999999.0, 0.0];
+    ) -> Option<Hit> {
+        let half_width = grid_width as f64 / 2.0;
+        let half_height = grid_height as f64 / 2.0;
         
-        let texture_height = earth.len();
-        if texture_height == 0 {
-            return;
-        }
+        let o: Vec3 = [self.x, self.y, self.z];
+        let mut u: Vec3 = [
+            -(px - half_width) / half_width * 1.2,
+            (py - half_height) / half_height,
+            -1.0,
+        ];
+        
+        u = math::transform_vector(u, &self.matrix);
+        u = [
+            u[0] - self.x,
+            u[1] - self.y,
+            u[2] - self.z,
+        ];
+        u = math::normalize(u);
         
-        let texture_width = earth[0].len();
-        if texture_width == 0 {
-            return;
+        let discriminant = math::dot(u, o) * math::dot(u, o) - math::dot(o, o) + radius * radius;
+        if discriminant < 0.0 {
+            return None;
         }
         
-        let radius = radius * scale;
-        let tilt_rad = tilt.to_radians();
+        let distance = -discriminant.sqrt() - math::dot(u, o);
+        let inter: Vec3 = [
+            o[0] + distance * u[0],
+            o[1] + distance * u[1],
+            o[2] + distance * u[2],
+        ];
+        
+        let temp = math::rotate_x(inter, -tilt.to_radians());
         
-        for yi in 0..canvas_height {
-            for xi in 0..canvas_width {
-                let o: Vec3 = [self.x, self.y, self.z];
-                let mut u: Vec3 = [
-                    -((xi as f64 - (canvas_width as f64) / 2.0) + 0.5) / (canvas_width as f64 / 2.0) * 1.2,
-                    ((yi as f64 - (canvas_height as f64) / 2.0) + 0.5) / (canvas_height as f64 / 2.0),
-                    -1.0,
-                ];
-                
-                u = math::transform_vector(u, &self.matrix);
-                u = [
-                    u[0] - self.x,
-                    u[1] - self.y,
-                    u[2] - self.z,
-                ];
-                u = math::normalize(u);
-                
-                let discriminant = math::dot(u, o) * math::dot(u, o) - math::dot(o, o) + radius * radius;
-                if discriminant < 0.0 {
-                    continue;
-                }
-                
-                let distance = -discriminant.sqrt() - math::dot(u, o);
-                let inter: Vec3 = [
-                    o[0] + distance * u[0],
-                    o[1] + distance * u[1],
-                    o[2] + distance * u[2],
-                ];
-                
-                let n = math::normalize(inter);
-                let l = math::normalize(math::vector(light, inter));
-                let luminance = if lighting {
-                    clamp(5.0 * math::dot(n, l) + 0.5, 0.0, 1.0)
-                } else {
-                    1.0
-                };
-                
-                let temp = math::rotate_x(inter, -tilt_rad);
-                
-                let phi = -temp[2] / radius / 2.0 + 0.5;
-                let mut theta = -temp[1].atan2(temp[0]) / math::PI_CONST + 0.5 + angle_offset / 2.0 / math::PI_CONST;
-                theta -= theta.floor();
-                
-                let earth_x = clamp_int((theta * (texture_width - 1) as f64) as i32, 0, (texture_width - 1) as i32);
-                let earth_y = clamp_int((phi * (texture_height - 1) as f64) as i32, 0, (texture_height - 1) as i32);
-                
-                if let (Some(day_char), Some(night_char)) = (
-                    earth.get(earth_y as usize).and_then(|row| row.get(earth_x as usize)),
-                    earth_night.get(earth_y as usize).and_then(|row| row.get(earth_x as usize)),
-                ) {
-                    let day = find_index(*day_char, PALETTE);
-                    let night = find_index(*night_char, PALETTE);
-                    
-                    if day >= 0 && night >= 0 {
-                        let index = ((1.0 - luminance) * night as f64 + luminance * day as f64) as usize;
-                        let index = clamp_int(index as i32, 0, (PALETTE.len() - 1) as i32) as usize;
-                        draw_point(canvas, xi, yi, PALETTE.chars().nth(index).unwrap_or(' '), canvas_width, canvas_height);
-                    }
-                }
+        // One trip round the equator covers the texture once, so longitudes
+        // line up with `GlobeRenderer::surface_direction`
+        Some(Hit {
+            normal: math::normalize(inter),
+            u: -temp[1].atan2(temp[0]) / (2.0 * math::PI_CONST) + 0.5,
+            v: -temp[2] / radius / 2.0 + 0.5,
+        })
+    }
+}
+
+/// Shade the surface at `hit` for the current rotation and lighting
+pub fn shade(hit: &Hit, body: &Body, params: &SphereParams) -> Option<Shade> {
+    let texture_height = body.day.len();
+    let texture_width = body.day.first().map(|row| row.len()).unwrap_or(0);
+    if texture_height == 0 || texture_width == 0 {
+        return None;
+    }
+    
+    let luminance = match params.light {
+        Some(l) => clamp(5.0 * math::dot(hit.normal, l) + 0.5, 0.0, 1.0),
+        None => 1.0,
+    };
+    
+    let texture_y = texture_coord(hit.v, texture_height);
+    let texture_x = texture_coord(wrap(hit.u + params.angle_offset / 2.0 / math::PI_CONST), texture_width);
+    
+    let day = sample(&body.day, texture_x, texture_y).and_then(|c| body.palette_index(c))?;
+    // Bodies without a night texture go dark on the unlit side
+    let night = match &body.night {
+        Some(night) => sample(night, texture_x, texture_y).and_then(|c| body.palette_index(c))?,
+        None => 0,
+    };
+    
+    let max_index = body.palette.len() - 1;
+    let level = (1.0 - luminance) * night as f64 + luminance * day as f64;
+    let mut rgb = if params.color {
+        let day_rgb = body.day_color(day as f64 / max_index as f64);
+        let night_rgb = body.night_color(night as f64 / max_index as f64);
+        lerp_rgb(night_rgb, day_rgb, luminance)
+    } else {
+        [0, 0, 0]
+    };
+    
+    let mut index = level;
+    // Clouds drift independently of the surface and are only visible where lit
+    if let Some(clouds) = &body.clouds {
+        let cloud_x = texture_coord(wrap(hit.u + params.cloud_offset / 2.0 / math::PI_CONST), texture_width);
+        if let Some(cloud) = sample(clouds, cloud_x, texture_y).and_then(|c| body.palette_index(c)) {
+            let cloud_level = cloud as f64 * luminance;
+            index = index.max(cloud_level);
+            if params.color {
+                rgb = lerp_rgb(rgb, [255, 255, 255], cloud_level / max_index as f64);
             }
         }
     }
+    
+    let level = clamp(index / max_index as f64, 0.0, 1.0);
+    let index = clamp_int(index as i32, 0, max_index as i32) as usize;
+    Some(Shade {
+        ch: body.palette[index],
+        level,
+        rgb,
+    })
 }
 
-fn find_index(c: char, s: &str) -> i32 {
-    s.chars().position(|x| x == c).map(|i| i as i32).unwrap_or(-1)
+fn wrap(t: f64) -> f64 {
+    t - t.floor()
 }
 
-fn draw_point(canvas: &mut [Vec<char>], x: usize, y: usize, c: char, canvas_width: usize, canvas_height: usize) {
-    if x < canvas_width && y < canvas_height {
-        if let Some(row) = canvas.get_mut(y) {
-            if let Some(cell) = row.get_mut(x) {
-                *cell = c;
-            }
-        }
-    }
+fn texture_coord(t: f64, size: usize) -> usize {
+    clamp_int((t * (size - 1) as f64) as i32, 0, (size - 1) as i32) as usize
+}
+
+fn sample(texture: &[Vec<char>], x: usize, y: usize) -> Option<char> {
+    texture.get(y).and_then(|row| row.get(x)).copied()
+}
+
+pub fn lerp_rgb(a: [u8; 3], b: [u8; 3], t: f64) -> [u8; 3] {
+    let t = clamp(t, 0.0, 1.0);
+    let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+    [mix(a[0], b[0]), mix(a[1], b[1]), mix(a[2], b[2])]
 }
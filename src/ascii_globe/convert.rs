@@ -0,0 +1,296 @@
+use crate::ascii_globe::body::BodyManifest;
+use crate::ascii_globe::camera::PALETTE;
+use crate::ascii_globe::texture::save_texture;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Greyscale image with brightness from 0.0 (black) to 1.0 (white)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<f64>,
+}
+
+impl Image {
+    fn pixel(&self, x: usize, y: usize) -> f64 {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Settings for `convert`
+pub struct ConvertOptions {
+    pub name: String,
+    /// Texture size in characters
+    pub width: usize,
+    pub height: usize,
+    /// Characters from darkest to brightest; `camera::PALETTE` if `None`
+    pub palette: Option<String>,
+}
+
+/// Read a PGM or PPM image (`P2`, `P3`, `P5` or `P6`). Colour images are
+/// converted to greyscale.
+pub fn read_image(path: &Path) -> Result<Image> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read image {:?}", path))?;
+    if bytes.starts_with(b"\x89PNG") {
+        anyhow::bail!(
+            "{:?} is a PNG; convert it to PPM first, e.g. `convert {} out.ppm`",
+            path,
+            path.display()
+        );
+    }
+    parse_pnm(&bytes).with_context(|| format!("Failed to decode image {:?}", path))
+}
+
+/// Decode a binary or plain-text PGM/PPM image
+fn parse_pnm(bytes: &[u8]) -> Result<Image> {
+    let mut pos = 0;
+    let magic = next_token(bytes, &mut pos).context("Missing header")?;
+    let (channels, binary) = match magic {
+        b"P2" => (1, false),
+        b"P3" => (3, false),
+        b"P5" => (1, true),
+        b"P6" => (3, true),
+        _ => anyhow::bail!("Unsupported format; expected a PGM or PPM image (P2, P3, P5 or P6)"),
+    };
+
+    let mut header_value = |name: &str| -> Result<usize> {
+        let token = next_token(bytes, &mut pos).with_context(|| format!("Missing {}", name))?;
+        std::str::from_utf8(token)
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .with_context(|| format!("Invalid {}", name))
+    };
+    let width = header_value("width")?;
+    let height = header_value("height")?;
+    let max_value = header_value("maximum value")?;
+    if width == 0 || height == 0 {
+        anyhow::bail!("Image is empty");
+    }
+    if max_value == 0 || max_value > 65535 {
+        anyhow::bail!("Maximum value {} is out of range", max_value);
+    }
+
+    let count = width * height * channels;
+    let samples: Vec<usize> = if binary {
+        // A single whitespace character separates the header from the data
+        let data = &bytes[(pos + 1).min(bytes.len())..];
+        let sample_size = if max_value > 255 { 2 } else { 1 };
+        if data.len() < count * sample_size {
+            anyhow::bail!("Image data is truncated");
+        }
+        data.chunks(sample_size)
+            .take(count)
+            .map(|c| c.iter().fold(0, |value, &b| value << 8 | b as usize))
+            .collect()
+    } else {
+        let mut samples = Vec::with_capacity(count);
+        for _ in 0..count {
+            let token = next_token(bytes, &mut pos).context("Image data is truncated")?;
+            let value = std::str::from_utf8(token)
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .context("Invalid sample")?;
+            samples.push(value);
+        }
+        samples
+    };
+
+    let scale = max_value as f64;
+    let pixels = samples
+        .chunks(channels)
+        .map(|p| match p {
+            [grey] => *grey as f64 / scale,
+            // Rec. 709 luma
+            [r, g, b] => (0.2126 * *r as f64 + 0.7152 * *g as f64 + 0.0722 * *b as f64) / scale,
+            _ => 0.0,
+        })
+        .map(|v| v.clamp(0.0, 1.0))
+        .collect();
+
+    Ok(Image { width, height, pixels })
+}
+
+/// Next whitespace-separated header token, skipping `#` comments. Leaves
+/// `pos` on the byte after the token.
+fn next_token<'a>(bytes: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    loop {
+        while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if *pos < bytes.len() && bytes[*pos] == b'#' {
+            while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                *pos += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    let start = *pos;
+    while *pos < bytes.len() && !bytes[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    (*pos > start).then(|| &bytes[start..*pos])
+}
+
+/// Resample equirectangular `image` to a `width` x `height` character
+/// texture, averaging the pixels under each cell and mapping brightness
+/// onto `palette`. Texture rows are evenly spaced in the sine of latitude,
+/// as the globe samples them, so each covers more of the image's rows
+/// towards the poles.
+pub fn image_to_texture(image: &Image, width: usize, height: usize, palette: &[char]) -> Vec<Vec<char>> {
+    // Source pixels from `start` to `end` (fractions of `size`), at least one
+    let pixels = |start: f64, end: f64, size: usize| {
+        let first = ((start * size as f64).floor() as usize).min(size - 1);
+        let last = ((end * size as f64).ceil() as usize).clamp(first + 1, size);
+        first..last
+    };
+    // Image row fraction of texture row fraction `v`: the texture is linear
+    // in sin(latitude), the image in latitude
+    let image_v = |v: f64| 0.5 - (1.0 - 2.0 * v).clamp(-1.0, 1.0).asin() / std::f64::consts::PI;
+    let max_index = (palette.len() - 1) as f64;
+
+    (0..height)
+        .map(|ty| {
+            let rows = pixels(image_v(ty as f64 / height as f64), image_v((ty + 1) as f64 / height as f64), image.height);
+            (0..width)
+                .map(|tx| {
+                    let cols = pixels(tx as f64 / width as f64, (tx + 1) as f64 / width as f64, image.width);
+                    let mut total = 0.0;
+                    let mut count = 0;
+                    for y in rows.clone() {
+                        for x in cols.clone() {
+                            total += image.pixel(x, y);
+                            count += 1;
+                        }
+                    }
+                    let brightness = total / count as f64;
+                    palette[(brightness * max_index).round() as usize]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Convert equirectangular `day` (and optional `night`) images into
+/// textures plus a manifest in `out_dir`, named after `options.name`.
+/// Returns the manifest path.
+pub fn convert(day: &Path, night: Option<&Path>, out_dir: &Path, options: &ConvertOptions) -> Result<PathBuf> {
+    if options.width == 0 || options.height == 0 {
+        anyhow::bail!("Texture size must be at least 1x1");
+    }
+    let palette: Vec<char> = options.palette.as_deref().unwrap_or(PALETTE).chars().collect();
+    if palette.len() < 2 {
+        anyhow::bail!("Palette needs at least two characters");
+    }
+
+    fs::create_dir_all(out_dir).with_context(|| format!("Failed to create {:?}", out_dir))?;
+    let stem: String = options
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+
+    let write = |image_path: &Path, file_name: String| -> Result<PathBuf> {
+        let image = read_image(image_path)?;
+        let texture = image_to_texture(&image, options.width, options.height, &palette);
+        save_texture(out_dir.join(&file_name), &texture)?;
+        Ok(PathBuf::from(file_name))
+    };
+
+    let mut manifest = BodyManifest::new(&options.name, write(day, format!("{}.txt", stem))?, out_dir);
+    manifest.night = night.map(|path| write(path, format!("{}_night.txt", stem))).transpose()?;
+    manifest.palette = options.palette.clone();
+
+    let manifest_path = out_dir.join(format!("{}.toml", stem));
+    manifest.save(&manifest_path)?;
+    Ok(manifest_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::ascii_globe::body::Body;
+
+    #[test]
+    fn test_parse_plain_pgm() {
+        let image = parse_pnm(b"P2\n# a comment\n2 2\n10\n0 10\n5 10\n").unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.pixels, vec![0.0, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_binary_ppm() {
+        let mut bytes = b"P6 2 1 255\n".to_vec();
+        bytes.extend_from_slice(&[255, 255, 255, 0, 255, 0]);
+        let image = parse_pnm(&bytes).unwrap();
+        assert_eq!(image.pixels.len(), 2);
+        assert!((image.pixels[0] - 1.0).abs() < 1e-9);
+        assert!((image.pixels[1] - 0.7152).abs() < 1e-9);
+
+        let mut wide = b"P5 1 1 65535\n".to_vec();
+        wide.extend_from_slice(&[0x80, 0x00]);
+        assert!((parse_pnm(&wide).unwrap().pixels[0] - 0.5).abs() < 1e-4);
+
+        assert!(parse_pnm(b"P6 2 1 255\n\x00").is_err());
+        assert!(parse_pnm(b"P4 1 1\n").is_err());
+    }
+
+    #[test]
+    fn test_image_to_texture() {
+        // Left half black, right half white, downsampled 4x2 -> 2x1
+        let image = Image {
+            width: 4,
+            height: 2,
+            pixels: vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0],
+        };
+        let palette = [' ', '.', '@'];
+        assert_eq!(image_to_texture(&image, 2, 1, &palette), vec![vec![' ', '@']]);
+        // Averaging across the edge gives the middle of the palette
+        assert_eq!(image_to_texture(&image, 1, 1, &palette), vec![vec!['.']]);
+        // Upsampling repeats pixels
+        assert_eq!(image_to_texture(&image, 8, 1, &palette)[0].len(), 8);
+    }
+
+    #[test]
+    fn test_image_rows_resampled_by_latitude() {
+        // One row per degree, bright north of 54°N
+        let image = Image {
+            width: 1,
+            height: 180,
+            pixels: (0..180).map(|y| if y < 36 { 1.0 } else { 0.0 }).collect(),
+        };
+        let texture = image_to_texture(&image, 1, 10, &[' ', '.', ':', '@']);
+        // The first row reaches down to 53°N, so the bright cap stays within
+        // it rather than spreading over the first two rows
+        assert_eq!(texture[0], ['@']);
+        assert_eq!(texture[1], [' ']);
+        assert_eq!(texture[9], [' ']);
+    }
+
+    #[test]
+    fn test_convert_writes_loadable_body() {
+        let dir = TempDir::new("convert");
+        let day = dir.join("day.pgm");
+        let night = dir.join("night.pgm");
+        fs::write(&day, "P2 4 2 255\n0 64 128 255\n255 128 64 0\n").unwrap();
+        fs::write(&night, "P2 4 2 255\n0 0 0 0\n0 0 255 0\n").unwrap();
+
+        let options = ConvertOptions {
+            name: "Test World".to_string(),
+            width: 8,
+            height: 4,
+            palette: None,
+        };
+        let manifest_path = convert(&day, Some(&night), &dir.join("out"), &options).unwrap();
+        assert!(manifest_path.ends_with("test_world.toml"));
+
+        let body = Body::load(&BodyManifest::load(&manifest_path).unwrap()).unwrap();
+        assert_eq!(body.name, "Test World");
+        assert_eq!((body.day[0].len(), body.day.len()), (8, 4));
+        assert!(body.night.is_some());
+    }
+}
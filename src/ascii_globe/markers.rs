@@ -0,0 +1,253 @@
+use crate::ascii_globe::camera::Cell;
+use crate::config::GlobeConfig;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Points sampled along each great-circle arc
+const ARC_SAMPLES: usize = 96;
+
+/// A labelled point on the globe
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub glyph: char,
+    pub color: [u8; 3],
+}
+
+/// Great-circle arc between two points, in degrees
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arc {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub color: [u8; 3],
+}
+
+/// Build the markers and arcs described by the `[globe]` config. Arcs whose
+/// endpoints don't match a marker label are dropped.
+pub fn from_config(config: &GlobeConfig) -> (Vec<Marker>, Vec<Arc>) {
+    let mut markers: Vec<Marker> = config
+        .markers
+        .iter()
+        .map(|m| Marker {
+            label: m.label.clone(),
+            lat: m.lat,
+            lon: m.lon,
+            glyph: m.glyph,
+            color: m.color,
+        })
+        .collect();
+
+    if config.home_marker {
+        let home = match &config.home {
+            Some(home) => Some((home.label.clone(), home.lat, home.lon)),
+            None => timezone_location(),
+        };
+        if let Some((label, lat, lon)) = home {
+            markers.push(Marker {
+                label,
+                lat,
+                lon,
+                glyph: '⌂',
+                color: [120, 220, 255],
+            });
+        }
+    }
+
+    let find = |label: &str| {
+        markers
+            .iter()
+            .find(|m| m.label.eq_ignore_ascii_case(label))
+            .map(|m| (m.lat, m.lon))
+    };
+    let arcs = config
+        .arcs
+        .iter()
+        .filter_map(|a| {
+            Some(Arc {
+                from: find(&a.from)?,
+                to: find(&a.to)?,
+                color: a.color,
+            })
+        })
+        .collect();
+
+    (markers, arcs)
+}
+
+/// Unit vector for `lat`/`lon` in the globe's unrotated texture frame
+pub fn lat_lon_to_unit(lat: f64, lon: f64) -> [f64; 3] {
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+/// Inverse of `lat_lon_to_unit`
+pub fn unit_to_lat_lon(v: [f64; 3]) -> (f64, f64) {
+    let lat = v[2].clamp(-1.0, 1.0).asin().to_degrees();
+    let lon = v[1].atan2(v[0]).to_degrees();
+    (lat, lon)
+}
+
+/// Evenly spaced points (lat, lon) along the shorter great circle from
+/// `from` to `to`, inclusive of both ends
+pub fn great_circle(from: (f64, f64), to: (f64, f64), samples: usize) -> Vec<(f64, f64)> {
+    let a = lat_lon_to_unit(from.0, from.1);
+    let b = lat_lon_to_unit(to.0, to.1);
+    let cos_omega = (a[0] * b[0] + a[1] * b[1] + a[2] * b[2]).clamp(-1.0, 1.0);
+    let omega = cos_omega.acos();
+    if omega.abs() < 1e-9 || samples < 2 {
+        return vec![from, to];
+    }
+
+    (0..samples)
+        .map(|i| {
+            let t = i as f64 / (samples - 1) as f64;
+            let wa = ((1.0 - t) * omega).sin() / omega.sin();
+            let wb = (t * omega).sin() / omega.sin();
+            unit_to_lat_lon([
+                wa * a[0] + wb * b[0],
+                wa * a[1] + wb * b[1],
+                wa * a[2] + wb * b[2],
+            ])
+        })
+        .collect()
+}
+
+/// Points to sample for `arc`
+pub fn arc_points(arc: &Arc) -> Vec<(f64, f64)> {
+    great_circle(arc.from, arc.to, ARC_SAMPLES)
+}
+
+/// Write `text` into `canvas` starting at (`x`, `y`), clipped to the canvas
+pub fn draw_text(canvas: &mut [Vec<Cell>], x: usize, y: usize, text: &str, color: [u8; 3]) {
+    let Some(row) = canvas.get_mut(y) else {
+        return;
+    };
+    for (cell, ch) in row.iter_mut().skip(x).zip(text.chars()) {
+        *cell = Cell { ch, fg: Some(color), bg: None };
+    }
+}
+
+/// City and coordinates of the system timezone, looked up in the tz
+/// database's `zone1970.tab`/`zone.tab`
+pub fn timezone_location() -> Option<(String, f64, f64)> {
+    let zone = system_timezone()?;
+    ["/usr/share/zoneinfo/zone1970.tab", "/usr/share/zoneinfo/zone.tab"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|table| lookup_zone(&table, &zone))
+}
+
+fn system_timezone() -> Option<String> {
+    if let Ok(tz) = env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if tz.contains('/') {
+            return Some(tz.to_string());
+        }
+    }
+    if let Ok(tz) = fs::read_to_string("/etc/timezone") {
+        let tz = tz.trim();
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+    let target = fs::read_link(Path::new("/etc/localtime")).ok()?;
+    let target = target.to_string_lossy();
+    target
+        .split_once("zoneinfo/")
+        .map(|(_, zone)| zone.to_string())
+}
+
+fn lookup_zone(table: &str, zone: &str) -> Option<(String, f64, f64)> {
+    table
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let mut fields = line.split('\t');
+            let _countries = fields.next()?;
+            let coordinates = fields.next()?;
+            let name = fields.next()?;
+            if name != zone {
+                return None;
+            }
+            let (lat, lon) = parse_iso6709(coordinates)?;
+            let city = name.rsplit('/').next().unwrap_or(name).replace('_', " ");
+            Some((city, lat, lon))
+        })
+}
+
+/// Parse tz database coordinates such as `+5230+01322` or `-0545+14245`
+/// (±DDMM±DDDMM, optionally with seconds) into decimal degrees
+fn parse_iso6709(s: &str) -> Option<(f64, f64)> {
+    let split = s.get(1..)?.find(['+', '-'])? + 1;
+    let (lat, lon) = s.split_at(split);
+    Some((parse_dms(lat, 2)?, parse_dms(lon, 3)?))
+}
+
+fn parse_dms(s: &str, degree_digits: usize) -> Option<f64> {
+    let sign = match s.chars().next()? {
+        '+' => 1.0,
+        '-' => -1.0,
+        _ => return None,
+    };
+    let digits = &s[1..];
+    if digits.len() < degree_digits + 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let degrees: f64 = digits[..degree_digits].parse().ok()?;
+    let minutes: f64 = digits[degree_digits..degree_digits + 2].parse().ok()?;
+    let seconds: f64 = match &digits[degree_digits + 2..] {
+        "" => 0.0,
+        rest => rest.parse().ok()?,
+    };
+    Some(sign * (degrees + minutes / 60.0 + seconds / 3600.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso6709() {
+        let (lat, lon) = parse_iso6709("+5230+01322").unwrap();
+        assert!((lat - 52.5).abs() < 1e-9);
+        assert!((lon - 13.366_666).abs() < 1e-3);
+
+        let (lat, lon) = parse_iso6709("-334052+0583422").unwrap();
+        assert!((lat + 33.681_111).abs() < 1e-3);
+        assert!((lon - 58.572_777).abs() < 1e-3);
+
+        assert!(parse_iso6709("garbage").is_none());
+        assert!(parse_iso6709("").is_none());
+        assert!(parse_iso6709("é+1").is_none());
+    }
+
+    #[test]
+    fn test_lookup_zone() {
+        let table = "# comment\nDE,DK\t+5230+01322\tEurope/Berlin\nUS\t+404251-0740023\tAmerica/New_York\tEastern\n";
+        let (city, lat, _) = lookup_zone(table, "America/New_York").unwrap();
+        assert_eq!(city, "New York");
+        assert!((lat - 40.714).abs() < 1e-2);
+        assert!(lookup_zone(table, "Asia/Tokyo").is_none());
+
+        // Malformed lines are skipped rather than panicking
+        let table = "XX\t\tEurope/Nowhere\nYY\t\u{e9}+0100+00100\tEurope/Odd\n";
+        assert!(lookup_zone(table, "Europe/Nowhere").is_none());
+        assert!(lookup_zone(table, "Europe/Odd").is_none());
+    }
+
+    #[test]
+    fn test_great_circle_endpoints_and_midpoint() {
+        let points = great_circle((0.0, 0.0), (0.0, 90.0), 3);
+        assert_eq!(points.len(), 3);
+        assert!((points[1].0).abs() < 1e-9);
+        assert!((points[1].1 - 45.0).abs() < 1e-9);
+        assert!((points[2].1 - 90.0).abs() < 1e-9);
+
+        // Routes between mid-latitude points bow towards the pole
+        let points = great_circle((40.0, -74.0), (52.0, 13.0), 9);
+        assert!(points[4].0 > 52.0);
+    }
+}
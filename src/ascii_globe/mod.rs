@@ -1,6 +1,11 @@
+pub mod body;
 pub mod camera;
+pub mod convert;
+pub mod markers;
 pub mod math;
+pub mod raster;
 pub mod texture;
 pub mod renderer;
+pub mod sun;
 
 pub use renderer::GlobeRenderer;
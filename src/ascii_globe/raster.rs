@@ -0,0 +1,220 @@
+use crate::ascii_globe::body::Body;
+use crate::ascii_globe::camera::{self, Camera, Cell, Hit, Shade, SphereParams};
+use crate::config::RenderMode;
+
+// Ordered-dither thresholds for the 2x4 braille dot grid, indexed [row][col]
+const BRAILLE_DITHER: [[f64; 2]; 4] = [
+    [0.5 / 8.0, 4.5 / 8.0],
+    [6.5 / 8.0, 2.5 / 8.0],
+    [1.5 / 8.0, 5.5 / 8.0],
+    [7.5 / 8.0, 3.5 / 8.0],
+];
+
+// Bit for each braille dot, indexed [row][col] (Unicode braille patterns, U+2800)
+const BRAILLE_BITS: [[u32; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+/// Inputs that decide where rays hit the sphere
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeometryKey {
+    pub width: usize,
+    pub height: usize,
+    /// Samples per cell, (columns, rows)
+    pub samples: (usize, usize),
+    pub radius: f64,
+    pub tilt: f64,
+    pub pitch: f64,
+}
+
+/// Ray/sphere hits for every sample of a canvas. They depend on the canvas
+/// size, render mode and view but not on the globe's rotation, so they're
+/// computed once and reused while the globe turns.
+pub struct Geometry {
+    pub key: GeometryKey,
+    hits: Vec<Option<Hit>>,
+}
+
+impl Geometry {
+    /// Cast every ray for `key`. `camera` must be the one `key.pitch` describes.
+    pub fn new(camera: &Camera, key: GeometryKey) -> Self {
+        let (cols, rows) = key.samples;
+        let grid_width = key.width * cols;
+        let grid_height = key.height * rows;
+        let mut hits = Vec::with_capacity(grid_width * grid_height);
+        for gy in 0..grid_height {
+            for gx in 0..grid_width {
+                let px = gx as f64 + 0.5;
+                let py = gy as f64 + 0.5;
+                hits.push(camera.intersect(px, py, grid_width, grid_height, key.radius, key.tilt));
+            }
+        }
+        Self { key, hits }
+    }
+
+    fn hit(&self, xi: usize, yi: usize, sx: usize, sy: usize) -> Option<&Hit> {
+        let (cols, rows) = self.key.samples;
+        let index = (yi * rows + sy) * self.key.width * cols + xi * cols + sx;
+        self.hits.get(index).and_then(Option::as_ref)
+    }
+}
+
+/// Samples per cell, (columns, rows), used by the rasteriser for `mode`
+pub fn samples_per_cell(mode: RenderMode) -> (usize, usize) {
+    match mode {
+        RenderMode::Ascii | RenderMode::Color => (1, 1),
+        RenderMode::HalfBlock => (1, 2),
+        RenderMode::Braille => (2, 4),
+    }
+}
+
+/// Fill `canvas` with the globe using the rasteriser for `mode`. `geometry`
+/// must have been built with `samples_per_cell(mode)`. Cells the sphere
+/// doesn't cover are left untouched.
+pub fn rasterize(
+    mode: RenderMode,
+    geometry: &Geometry,
+    body: &Body,
+    params: &SphereParams,
+    canvas: &mut [Vec<Cell>],
+) {
+    let GeometryKey { width, height, .. } = geometry.key;
+    for (yi, row) in canvas.iter_mut().enumerate().take(height) {
+        for (xi, cell) in row.iter_mut().enumerate().take(width) {
+            let sampler = Sampler { geometry, body, params, xi, yi };
+            let rasterized = match mode {
+                RenderMode::Ascii | RenderMode::Color => palette_cell(&sampler),
+                RenderMode::HalfBlock => half_block_cell(&sampler),
+                RenderMode::Braille => braille_cell(&sampler),
+            };
+            if let Some(rasterized) = rasterized {
+                *cell = rasterized;
+            }
+        }
+    }
+}
+
+/// Samples sub-cell positions of the cell at (`xi`, `yi`)
+struct Sampler<'a> {
+    geometry: &'a Geometry,
+    body: &'a Body,
+    params: &'a SphereParams,
+    xi: usize,
+    yi: usize,
+}
+
+impl Sampler<'_> {
+    /// Shade sample (`sx`, `sy`) of the cell
+    fn shade(&self, sx: usize, sy: usize) -> Option<Shade> {
+        let hit = self.geometry.hit(self.xi, self.yi, sx, sy)?;
+        camera::shade(hit, self.body, self.params)
+    }
+}
+
+/// One ray per cell, drawn with the body's palette characters
+fn palette_cell(sampler: &Sampler) -> Option<Cell> {
+    sampler.shade(0, 0).map(|shade| Cell {
+        ch: shade.ch,
+        fg: sampler.params.color.then_some(shade.rgb),
+        bg: None,
+    })
+}
+
+/// Two vertical samples per cell: the upper half is the foreground of `▀`
+/// and the lower half its background
+fn half_block_cell(sampler: &Sampler) -> Option<Cell> {
+    let top = sampler.shade(0, 0);
+    let bottom = sampler.shade(0, 1);
+    match (top, bottom) {
+        (Some(top), Some(bottom)) => Some(Cell { ch: '▀', fg: Some(top.rgb), bg: Some(bottom.rgb) }),
+        (Some(top), None) => Some(Cell { ch: '▀', fg: Some(top.rgb), bg: None }),
+        (None, Some(bottom)) => Some(Cell { ch: '▄', fg: Some(bottom.rgb), bg: None }),
+        (None, None) => None,
+    }
+}
+
+/// 2x4 samples per cell, one per braille dot. Brightness is dithered into
+/// dot density and the cell takes the average colour of its lit dots.
+fn braille_cell(sampler: &Sampler) -> Option<Cell> {
+    let mut bits = 0;
+    let mut hit = false;
+    let mut total = [0u32; 3];
+    let mut lit = 0;
+
+    for (row, thresholds) in BRAILLE_DITHER.iter().enumerate() {
+        for (col, &threshold) in thresholds.iter().enumerate() {
+            let Some(shade) = sampler.shade(col, row) else {
+                continue;
+            };
+            hit = true;
+            if shade.level > threshold {
+                bits |= BRAILLE_BITS[row][col];
+                for (sum, channel) in total.iter_mut().zip(shade.rgb) {
+                    *sum += channel as u32;
+                }
+                lit += 1;
+            }
+        }
+    }
+
+    if !hit {
+        return None;
+    }
+    let ch = char::from_u32(0x2800 + bits).unwrap_or(' ');
+    let fg = (sampler.params.color && lit > 0).then(|| total.map(|sum| (sum / lit) as u8));
+    Some(Cell { ch, fg, bg: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(mode: RenderMode, width: usize, height: usize) -> Vec<Vec<Cell>> {
+        let body = Body {
+            name: "Test".to_string(),
+            day: vec![vec!['@'; 8]; 4],
+            night: None,
+            clouds: None,
+            palette: " @".chars().collect(),
+            day_ramp: vec![[0, 0, 0], [200, 100, 50]],
+            night_ramp: vec![[0, 0, 0], [200, 100, 50]],
+        };
+        let params = SphereParams {
+            angle_offset: 0.0,
+            cloud_offset: 0.0,
+            light: None,
+            color: true,
+        };
+        let key = GeometryKey {
+            width,
+            height,
+            samples: samples_per_cell(mode),
+            radius: 1.0,
+            tilt: 0.0,
+            pitch: 0.0,
+        };
+        let geometry = Geometry::new(&Camera::new(2.0, 0.0, 0.0), key);
+        let mut canvas = vec![vec![Cell::BLANK; width]; height];
+        rasterize(mode, &geometry, &body, &params, &mut canvas);
+        canvas
+    }
+
+    #[test]
+    fn test_braille_fills_lit_centre() {
+        let canvas = render(RenderMode::Braille, 20, 10);
+        assert_eq!(canvas[5][10].ch, '⣿');
+        assert_eq!(canvas[5][10].fg, Some([200, 100, 50]));
+        assert_eq!(canvas[0][0], Cell::BLANK);
+    }
+
+    #[test]
+    fn test_half_block_uses_both_halves() {
+        let canvas = render(RenderMode::HalfBlock, 20, 10);
+        assert_eq!(canvas[5][10].ch, '▀');
+        assert_eq!(canvas[5][10].bg, Some([200, 100, 50]));
+        assert_eq!(canvas[0][0], Cell::BLANK);
+    }
+}
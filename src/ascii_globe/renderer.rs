@@ -1,58 +1,184 @@
-use crate::ascii_globe::camera::Camera;
-use crate::ascii_globe::math::PI_CONST;
-use crate::ascii_globe::texture::load_texture;
-use anyhow::{Context, Result};
+use crate::ascii_globe::body::Body;
+use crate::ascii_globe::camera::{Camera, Cell, SphereParams};
+use crate::ascii_globe::math::{self, Vec3, PI_CONST};
+use crate::ascii_globe::markers::{self, Arc, Marker};
+use crate::ascii_globe::raster::{self, Geometry, GeometryKey};
+use crate::ascii_globe::sun;
+use crate::config::{LightSource, RenderMode};
+use chrono::{DateTime, Duration, Utc};
+
+// Clouds drift slightly faster than the surface turns
+const CLOUD_DRIFT: f64 = 1.08;
+
+// Distance from the camera to the centre of the globe
+const CAMERA_DISTANCE: f64 = 2.0;
+// Keep the camera short of the poles so "up" stays well defined
+const MAX_PITCH: f64 = 1.3;
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 2.0;
+// Largest radius drawn, so zooming in never puts the camera inside the globe
+const MAX_RADIUS: f64 = CAMERA_DISTANCE * 0.9;
+// Fraction of spin lost per second once a drag is released
+const SPIN_DECAY: f64 = 2.5;
+// Spin below this (radians per second) stops completely
+const MIN_SPIN: f64 = 0.02;
 
 pub struct GlobeRenderer {
     camera: Camera,
-    earth: Vec<Vec<char>>,
-    earth_night: Vec<Vec<char>>,
+    body: Body,
     angle_offset: f64,
+    cloud_offset: f64,
     scale: f64,
     speed: f64,
     tilt: f64,
     lighting: bool,
+    light_source: LightSource,
+    // Simulated clock driving the sun position, advanced by `time_lapse`
+    sim_time: DateTime<Utc>,
+    time_lapse: f64,
+    render_mode: RenderMode,
+    markers: Vec<Marker>,
+    arcs: Vec<Arc>,
+    // Interactive view: camera elevation (radians), zoom multiplier on
+    // `scale` and whether the automatic rotation is paused
+    pitch: f64,
+    zoom: f64,
+    paused: bool,
+    // Rotation applied by the current drag since the last update, and the
+    // resulting angular velocity (yaw, pitch) in radians per second which
+    // carries on after the drag is released
+    dragging: bool,
+    drag_delta: (f64, f64),
+    spin: (f64, f64),
+    // Ray hits for the last canvas size and view, rebuilt when they change
+    geometry: Option<Geometry>,
+    // Bumped whenever the body, render mode or overlays change, so cached
+    // frames can tell they're stale
+    revision: u64,
+}
+
+/// Everything that affects a rendered frame. Two frames with equal keys are
+/// identical.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameKey {
+    width: usize,
+    height: usize,
+    angle_offset: f64,
+    cloud_offset: f64,
+    scale: f64,
+    tilt: f64,
+    pitch: f64,
+    light: Option<Vec3>,
+    revision: u64,
 }
 
 impl GlobeRenderer {
-    pub fn new(texture_dir: &str) -> Result<Self> {
-        let earth_path = format!("{}/earth.txt", texture_dir);
-        let earth_night_path = format!("{}/earth_night.txt", texture_dir);
-        
-        let earth = load_texture(&earth_path)
-            .with_context(|| format!("Failed to load {}", earth_path))?;
-        let earth_night = load_texture(&earth_night_path)
-            .with_context(|| format!("Failed to load {}", earth_night_path))?;
-        
-        if earth.is_empty() || earth_night.is_empty() {
-            anyhow::bail!("Failed to load textures");
-        }
-        
-        Ok(Self {
-            camera: Camera::new(2.0, 0.0, 0.0),
-            earth,
-            earth_night,
+    pub fn new(body: Body) -> Self {
+        Self {
+            camera: Camera::new(CAMERA_DISTANCE, 0.0, 0.0),
+            body,
             angle_offset: 0.0,
+            cloud_offset: 0.0,
             scale: 1.0,
             speed: 1.0,
             tilt: 23.5,
             lighting: true,
-        })
-    }
-    
-    pub fn render_frame(&mut self, canvas: &mut [Vec<char>], width: usize, height: usize) {
-        self.camera.render_sphere(
-            canvas,
-            1.0,
-            self.angle_offset,
-            &self.earth,
-            &self.earth_night,
-            self.scale,
-            self.tilt,
-            self.lighting,
+            light_source: LightSource::Fixed,
+            sim_time: Utc::now(),
+            time_lapse: 1.0,
+            render_mode: RenderMode::Ascii,
+            markers: Vec::new(),
+            arcs: Vec::new(),
+            pitch: 0.0,
+            zoom: 1.0,
+            paused: false,
+            dragging: false,
+            drag_delta: (0.0, 0.0),
+            spin: (0.0, 0.0),
+            geometry: None,
+            revision: 0,
+        }
+    }
+    
+    pub fn render_frame(&mut self, canvas: &mut [Vec<Cell>], width: usize, height: usize) {
+        let params = SphereParams {
+            angle_offset: self.angle_offset,
+            cloud_offset: self.cloud_offset,
+            light: self.light_direction(),
+            color: self.render_mode != RenderMode::Ascii,
+        };
+        
+        let key = GeometryKey {
             width,
             height,
-        );
+            samples: raster::samples_per_cell(self.render_mode),
+            radius: self.effective_scale(),
+            tilt: self.tilt,
+            pitch: self.pitch,
+        };
+        if self.geometry.as_ref().is_some_and(|g| g.key != key) {
+            self.geometry = None;
+        }
+        let geometry = self.geometry.get_or_insert_with(|| Geometry::new(&self.camera, key));
+        
+        raster::rasterize(self.render_mode, geometry, &self.body, &params, canvas);
+        self.draw_overlays(canvas, width, height);
+    }
+    
+    /// Key identifying the frame `render_frame` would draw at this size
+    pub fn frame_key(&self, width: usize, height: usize) -> FrameKey {
+        FrameKey {
+            width,
+            height,
+            angle_offset: self.angle_offset,
+            cloud_offset: self.cloud_offset,
+            scale: self.effective_scale(),
+            tilt: self.tilt,
+            pitch: self.pitch,
+            light: self.light_direction(),
+            revision: self.revision,
+        }
+    }
+    
+    /// Draw arcs, then markers and their labels, over the rendered globe.
+    /// Anything on the far side of the globe is hidden.
+    fn draw_overlays(&self, canvas: &mut [Vec<Cell>], width: usize, height: usize) {
+        for arc in &self.arcs {
+            for (lat, lon) in markers::arc_points(arc) {
+                if let Some((x, y)) = self.project_surface(lat, lon, width, height) {
+                    let cell = &mut canvas[y][x];
+                    *cell = Cell { ch: '·', fg: Some(arc.color), bg: cell.bg };
+                }
+            }
+        }
+        
+        for marker in &self.markers {
+            if let Some((x, y)) = self.project_surface(marker.lat, marker.lon, width, height) {
+                canvas[y][x] = Cell { ch: marker.glyph, fg: Some(marker.color), bg: None };
+                markers::draw_text(canvas, x + 2, y, &marker.label, marker.color);
+            }
+        }
+    }
+    
+    /// Canvas cell showing `lat`/`lon`, if it's on the visible hemisphere
+    fn project_surface(&self, lat: f64, lon: f64, width: usize, height: usize) -> Option<(usize, usize)> {
+        let radius = self.effective_scale();
+        let direction = self.surface_direction(lat, lon);
+        let point = [direction[0] * radius, direction[1] * radius, direction[2] * radius];
+        if !self.camera.faces(point) {
+            return None;
+        }
+        let (px, py) = self.camera.project(point, width, height)?;
+        if px < 0.0 || py < 0.0 || px >= width as f64 || py >= height as f64 {
+            return None;
+        }
+        Some((px as usize, py as usize))
+    }
+    
+    pub fn set_markers(&mut self, markers: Vec<Marker>, arcs: Vec<Arc>) {
+        self.markers = markers;
+        self.arcs = arcs;
+        self.revision += 1;
     }
     
     pub fn update(&mut self, delta_time: f64) {
@@ -61,10 +187,171 @@ impl GlobeRenderer {
         // For 60 FPS with 100ms sleep: delta_time ≈ 0.1s
         // Original increment per frame: (2.0 * PI / 18.0) * speed ≈ 0.349 * speed
         // So per second: 0.349 * speed * 10 ≈ 3.49 * speed radians/second
-        let rotation_rate = 3.49 * self.speed; // radians per second
-        self.angle_offset += rotation_rate * delta_time;
-        // Keep angle_offset in [0, 2π) range
-        self.angle_offset = self.angle_offset % (2.0 * PI_CONST);
+        if !self.paused {
+            // The texture used to wrap twice round the sphere; halve the rate
+            // so the surface still moves across the screen at the same speed
+            let rotation_rate = 3.49 / 2.0 * self.speed; // radians per second
+            self.angle_offset += rotation_rate * delta_time;
+            // Keep angle_offset in [0, 2π) range
+            self.angle_offset %= 2.0 * PI_CONST;
+            self.cloud_offset += rotation_rate * CLOUD_DRIFT * delta_time;
+            self.cloud_offset %= 2.0 * PI_CONST;
+        }
+        
+        if self.dragging {
+            // Smooth the drag velocity so a single jerky event doesn't dominate
+            if delta_time > 0.0 {
+                let velocity = (self.drag_delta.0 / delta_time, self.drag_delta.1 / delta_time);
+                self.spin = (
+                    (self.spin.0 + velocity.0) / 2.0,
+                    (self.spin.1 + velocity.1) / 2.0,
+                );
+            }
+            self.drag_delta = (0.0, 0.0);
+        } else if self.spin != (0.0, 0.0) {
+            self.rotate(self.spin.0 * delta_time, self.spin.1 * delta_time);
+            let decay = (-SPIN_DECAY * delta_time).exp();
+            self.spin = (self.spin.0 * decay, self.spin.1 * decay);
+            if self.spin.0.hypot(self.spin.1) < MIN_SPIN {
+                self.spin = (0.0, 0.0);
+            }
+        }
+        
+        if self.light_source == LightSource::Sun {
+            let simulated_ms = (delta_time * self.time_lapse * 1000.0) as i64;
+            self.sim_time += Duration::milliseconds(simulated_ms);
+        }
+    }
+    
+    /// Current spin about the globe's axis in radians, in [0, 2π)
+    pub fn rotation(&self) -> f64 {
+        self.angle_offset
+    }
+
+    /// Turn the globe so longitude `lon` (degrees) faces the camera
+    pub fn set_center_longitude(&mut self, lon: f64) {
+        self.angle_offset = lon.to_radians().rem_euclid(2.0 * PI_CONST);
+        self.cloud_offset = self.angle_offset;
+    }
+    
+    /// Turn the globe by `yaw` radians about its axis and move the camera
+    /// `pitch` radians towards the north pole
+    fn rotate(&mut self, yaw: f64, pitch: f64) {
+        self.angle_offset = (self.angle_offset + yaw).rem_euclid(2.0 * PI_CONST);
+        self.cloud_offset = (self.cloud_offset + yaw).rem_euclid(2.0 * PI_CONST);
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+        self.camera = Camera::new(CAMERA_DISTANCE, 0.0, self.pitch);
+    }
+    
+    /// Rotate by a fixed step, e.g. from a key press, cancelling any spin.
+    /// Positive `right`/`down` (radians) move the visible surface right/down.
+    pub fn nudge(&mut self, right: f64, down: f64) {
+        self.spin = (0.0, 0.0);
+        self.rotate(-right, down);
+    }
+    
+    /// Start a drag, stopping any spin left over from the previous one
+    pub fn begin_drag(&mut self) {
+        self.dragging = true;
+        self.drag_delta = (0.0, 0.0);
+        self.spin = (0.0, 0.0);
+    }
+    
+    /// Rotate with the pointer while dragging, with the same directions as
+    /// `nudge`
+    pub fn drag(&mut self, right: f64, down: f64) {
+        if !self.dragging {
+            self.begin_drag();
+        }
+        let (yaw, pitch) = (-right, down);
+        self.drag_delta = (self.drag_delta.0 + yaw, self.drag_delta.1 + pitch);
+        self.rotate(yaw, pitch);
+    }
+    
+    /// Release the drag; the globe keeps spinning with the drag's velocity
+    /// and slows down over time
+    pub fn end_drag(&mut self) {
+        self.dragging = false;
+        self.drag_delta = (0.0, 0.0);
+    }
+    
+    /// Multiply the zoom level by `factor`
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+    
+    /// Pause or resume the automatic rotation. Returns whether it's now paused.
+    pub fn toggle_pause(&mut self) -> bool {
+        self.paused = !self.paused;
+        self.paused
+    }
+    
+    /// Return the camera, zoom and rotation to their defaults
+    pub fn reset_view(&mut self) {
+        self.pitch = 0.0;
+        self.zoom = 1.0;
+        self.paused = false;
+        self.dragging = false;
+        self.drag_delta = (0.0, 0.0);
+        self.spin = (0.0, 0.0);
+        self.camera = Camera::new(CAMERA_DISTANCE, 0.0, 0.0);
+    }
+    
+    fn effective_scale(&self) -> f64 {
+        (self.scale * self.zoom).min(MAX_RADIUS)
+    }
+    
+    fn light_direction(&self) -> Option<Vec3> {
+        if !self.lighting {
+            return None;
+        }
+        match self.light_source {
+            LightSource::Fixed => Some([0.0, 1.0, 0.0]),
+            LightSource::Sun => {
+                let (lat, lon) = sun::subsolar_point(self.sim_time);
+                Some(self.surface_direction(lat, lon))
+            }
+        }
+    }
+    
+    /// World-space unit vector pointing at `lat`/`lon` (degrees) on the
+    /// textured surface, the inverse of the texture lookup in
+    /// `camera::shade` for the current rotation and tilt
+    pub fn surface_direction(&self, lat: f64, lon: f64) -> Vec3 {
+        // Texture rows map linearly onto the sphere's z axis
+        let z = (lat / 90.0).clamp(-1.0, 1.0);
+        let rho = (1.0 - z * z).sqrt();
+        let psi = self.angle_offset - lon.to_radians();
+        math::rotate_x([rho * psi.cos(), rho * psi.sin(), z], self.tilt.to_radians())
+    }
+    
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        self.render_mode = render_mode;
+        self.revision += 1;
+    }
+    
+    pub fn set_light_source(&mut self, light_source: LightSource) {
+        self.light_source = light_source;
+    }
+    
+    /// Speed of the simulated sun clock relative to real time
+    pub fn set_time_lapse(&mut self, time_lapse: f64) {
+        self.time_lapse = time_lapse;
+    }
+    
+    /// Jump the simulated sun clock to `time`
+    pub fn set_time(&mut self, time: DateTime<Utc>) {
+        self.sim_time = time;
+    }
+    
+    /// Swap the rendered body, keeping rotation and view settings
+    pub fn set_body(&mut self, body: Body) {
+        self.body = body;
+        self.revision += 1;
+    }
+    
+    pub fn body_name(&self) -> &str {
+        &self.body.name
     }
     
     pub fn set_scale(&mut self, scale: f64) {
@@ -83,6 +370,7 @@ impl GlobeRenderer {
         self.lighting = lighting;
     }
     
+    #[allow(dead_code)]
     pub fn get_scale(&self) -> f64 {
         self.scale
     }
@@ -102,3 +390,235 @@ impl GlobeRenderer {
         self.lighting
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn renderer() -> GlobeRenderer {
+        let mut renderer = GlobeRenderer::new(Body {
+            name: "Test".to_string(),
+            day: vec![vec!['@'; 8]; 4],
+            night: None,
+            clouds: None,
+            palette: " @".chars().collect(),
+            day_ramp: vec![[0, 0, 0], [255, 255, 255]],
+            night_ramp: vec![[0, 0, 0], [255, 255, 255]],
+        });
+        renderer.set_light_source(LightSource::Sun);
+        renderer
+    }
+
+    #[test]
+    fn test_sun_lights_north_pole_in_june() {
+        let mut renderer = renderer();
+        renderer.set_time(Utc.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap());
+        let light = renderer.light_direction().unwrap();
+        assert!(math::dot(light, renderer.surface_direction(90.0, 0.0)) > 0.0);
+        assert!(math::dot(light, renderer.surface_direction(-90.0, 0.0)) < 0.0);
+    }
+
+    #[test]
+    fn test_sun_lights_south_pole_in_december() {
+        let mut renderer = renderer();
+        renderer.set_time(Utc.with_ymd_and_hms(2024, 12, 21, 12, 0, 0).unwrap());
+        let light = renderer.light_direction().unwrap();
+        assert!(math::dot(light, renderer.surface_direction(90.0, 0.0)) < 0.0);
+        assert!(math::dot(light, renderer.surface_direction(-90.0, 0.0)) > 0.0);
+    }
+
+    #[test]
+    fn test_noon_meridian_faces_sun() {
+        let mut renderer = renderer();
+        renderer.set_time(Utc.with_ymd_and_hms(2024, 4, 15, 12, 0, 0).unwrap());
+        let light = renderer.light_direction().unwrap();
+        // Greenwich is at local noon, the antimeridian at midnight
+        assert!(math::dot(light, renderer.surface_direction(0.0, 0.0)) > 0.99);
+        assert!(math::dot(light, renderer.surface_direction(0.0, 180.0)) < -0.99);
+    }
+
+    #[test]
+    fn test_markers_hidden_on_far_side() {
+        let mut renderer = renderer();
+        renderer.set_tilt(0.0);
+        renderer.set_lighting(false);
+        // The camera looks at longitude 0 when the globe is unrotated
+        renderer.set_markers(
+            vec![
+                Marker { label: "Near".to_string(), lat: 0.0, lon: 0.0, glyph: 'N', color: [255, 0, 0] },
+                Marker { label: "Far".to_string(), lat: 0.0, lon: 180.0, glyph: 'F', color: [255, 0, 0] },
+            ],
+            Vec::new(),
+        );
+        let mut canvas = vec![vec![Cell::BLANK; 40]; 20];
+        renderer.render_frame(&mut canvas, 40, 20);
+        let text: String = canvas.iter().flatten().map(|c| c.ch).collect();
+        assert!(text.contains("Near"));
+        assert!(!text.contains('F'));
+        assert_eq!(canvas[10][20].ch, 'N');
+    }
+
+    #[test]
+    fn test_map_orientation() {
+        let mut renderer = renderer();
+        renderer.set_tilt(0.0);
+        let (x, y) = renderer.project_surface(0.0, 0.0, 40, 20).unwrap();
+        let (east_x, _) = renderer.project_surface(0.0, 30.0, 40, 20).unwrap();
+        let (_, north_y) = renderer.project_surface(45.0, 0.0, 40, 20).unwrap();
+        assert!(east_x > x, "{} -> {}", x, east_x);
+        assert!(north_y < y, "{} -> {}", y, north_y);
+    }
+
+    #[test]
+    fn test_texture_matches_surface_direction() {
+        // Bright bands at longitudes 45°E and 90°E
+        let mut day = vec![vec!['.'; 37]; 19];
+        for row in &mut day {
+            row[21..=23].fill('@');
+            row[27] = '@';
+        }
+        let mut renderer = GlobeRenderer::new(Body {
+            name: "Test".to_string(),
+            day,
+            night: None,
+            clouds: None,
+            palette: " .@".chars().collect(),
+            day_ramp: vec![[0, 0, 0], [255, 255, 255]],
+            night_ramp: vec![[0, 0, 0], [255, 255, 255]],
+        });
+        renderer.set_tilt(0.0);
+        renderer.set_lighting(false);
+        renderer.angle_offset = 90f64.to_radians();
+
+        let mut canvas = vec![vec![Cell::BLANK; 80]; 40];
+        renderer.render_frame(&mut canvas, 80, 40);
+        let (x, y) = renderer.project_surface(0.0, 90.0, 80, 40).unwrap();
+        assert_eq!(canvas[y][x].ch, '@');
+        let (x, y) = renderer.project_surface(0.0, 45.0, 80, 40).unwrap();
+        assert_eq!(canvas[y][x].ch, '@');
+        let (x, y) = renderer.project_surface(0.0, 67.5, 80, 40).unwrap();
+        assert_eq!(canvas[y][x].ch, '.');
+    }
+
+    #[test]
+    fn test_drag_moves_surface_with_pointer() {
+        let mut renderer = renderer();
+        renderer.set_tilt(0.0);
+        let (x, y) = renderer.project_surface(0.0, 0.0, 40, 20).unwrap();
+        renderer.drag(0.3, 0.0);
+        let (dragged_x, _) = renderer.project_surface(0.0, 0.0, 40, 20).unwrap();
+        assert!(dragged_x > x, "{} -> {}", x, dragged_x);
+        renderer.drag(0.0, 0.3);
+        let (_, dragged_y) = renderer.project_surface(0.0, 0.0, 40, 20).unwrap();
+        assert!(dragged_y > y, "{} -> {}", y, dragged_y);
+    }
+
+    #[test]
+    fn test_spin_continues_after_release_and_decays() {
+        let mut renderer = renderer();
+        renderer.toggle_pause();
+        renderer.drag(0.2, 0.0);
+        renderer.update(0.1);
+        renderer.end_drag();
+
+        let before = renderer.angle_offset;
+        renderer.update(0.1);
+        assert!(renderer.angle_offset != before);
+        for _ in 0..100 {
+            renderer.update(0.1);
+        }
+        assert_eq!(renderer.spin, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pause_and_reset() {
+        let mut renderer = renderer();
+        assert!(renderer.toggle_pause());
+        renderer.update(1.0);
+        assert_eq!(renderer.angle_offset, 0.0);
+
+        renderer.nudge(0.0, 10.0);
+        assert_eq!(renderer.pitch, MAX_PITCH);
+        renderer.zoom_by(100.0);
+        assert_eq!(renderer.zoom, MAX_ZOOM);
+        renderer.reset_view();
+        assert_eq!((renderer.pitch, renderer.zoom, renderer.paused), (0.0, 1.0, false));
+    }
+
+    #[test]
+    fn test_frame_key_tracks_changes() {
+        let mut renderer = renderer();
+        let key = renderer.frame_key(40, 20);
+        assert_eq!(key, renderer.frame_key(40, 20));
+        assert_ne!(key, renderer.frame_key(41, 20));
+
+        renderer.toggle_pause();
+        renderer.update(0.0);
+        assert_eq!(key, renderer.frame_key(40, 20));
+        renderer.toggle_pause();
+        renderer.update(0.1);
+        assert_ne!(key, renderer.frame_key(40, 20));
+
+        let key = renderer.frame_key(40, 20);
+        renderer.set_render_mode(RenderMode::Braille);
+        assert_ne!(key, renderer.frame_key(40, 20));
+    }
+
+    #[test]
+    fn test_geometry_reused_while_rotating() {
+        let mut renderer = renderer();
+        let mut canvas = vec![vec![Cell::BLANK; 40]; 20];
+        renderer.render_frame(&mut canvas, 40, 20);
+        let key = renderer.geometry.as_ref().map(|g| g.key);
+        renderer.update(0.1);
+        renderer.render_frame(&mut canvas, 40, 20);
+        assert_eq!(renderer.geometry.as_ref().map(|g| g.key), key);
+
+        renderer.zoom_by(1.5);
+        renderer.render_frame(&mut canvas, 40, 20);
+        assert_ne!(renderer.geometry.as_ref().map(|g| g.key), key);
+    }
+
+    // Compares rendering with cached ray hits against casting every ray each
+    // frame. Run with `cargo test --release bench_render_frame -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_render_frame() {
+        use std::time::Instant;
+
+        const FRAMES: u32 = 200;
+        let (width, height) = (120, 50);
+        let mut renderer = renderer();
+        renderer.set_render_mode(RenderMode::Braille);
+        let mut canvas = vec![vec![Cell::BLANK; width]; height];
+
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            renderer.geometry = None;
+            renderer.update(0.016);
+            renderer.render_frame(&mut canvas, width, height);
+        }
+        let uncached = start.elapsed() / FRAMES;
+
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            renderer.update(0.016);
+            renderer.render_frame(&mut canvas, width, height);
+        }
+        let cached = start.elapsed() / FRAMES;
+
+        println!("{}x{} braille: {:?}/frame uncached, {:?}/frame cached", width, height, uncached, cached);
+        assert!(cached < uncached);
+    }
+
+    #[test]
+    fn test_time_lapse_advances_clock() {
+        let mut renderer = renderer();
+        let start = Utc.with_ymd_and_hms(2024, 4, 15, 12, 0, 0).unwrap();
+        renderer.set_time(start);
+        renderer.set_time_lapse(3600.0);
+        renderer.update(1.0);
+        assert_eq!(renderer.sim_time, start + Duration::hours(1));
+    }
+}
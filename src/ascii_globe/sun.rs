@@ -0,0 +1,93 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::f64::consts::PI;
+
+/// Solar declination and equation of time for `time`, using the NOAA
+/// fractional-year approximation. Returns (declination in degrees, equation
+/// of time in minutes).
+pub fn solar_parameters(time: DateTime<Utc>) -> (f64, f64) {
+    let hours = time.hour() as f64 + time.minute() as f64 / 60.0 + time.second() as f64 / 3600.0;
+    let days_in_year = if is_leap_year(time.year()) { 366.0 } else { 365.0 };
+    let gamma = 2.0 * PI / days_in_year * (time.ordinal0() as f64 + (hours - 12.0) / 24.0);
+
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+
+    (declination.to_degrees(), equation_of_time)
+}
+
+/// Latitude and longitude in degrees of the point where the sun is directly
+/// overhead at `time`. Longitude is in [-180, 180).
+pub fn subsolar_point(time: DateTime<Utc>) -> (f64, f64) {
+    let (declination, equation_of_time) = solar_parameters(time);
+    let minutes = time.hour() as f64 * 60.0 + time.minute() as f64 + time.second() as f64 / 60.0;
+
+    // The sun crosses the meridian at 12:00 local solar time, 4 minutes per degree
+    let longitude = -(minutes - 720.0 + equation_of_time) / 4.0;
+    let longitude = (longitude + 180.0).rem_euclid(360.0) - 180.0;
+
+    (declination, longitude)
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_june_solstice_declination() {
+        let (lat, _) = subsolar_point(at(2024, 6, 20, 20, 51));
+        assert!((lat - 23.44).abs() < 0.3, "lat {}", lat);
+    }
+
+    #[test]
+    fn test_december_solstice_declination() {
+        let (lat, _) = subsolar_point(at(2024, 12, 21, 9, 20));
+        assert!((lat + 23.44).abs() < 0.3, "lat {}", lat);
+    }
+
+    #[test]
+    fn test_equinox_declination() {
+        // The fractional-year series is good to roughly half a degree
+        let (lat, _) = subsolar_point(at(2024, 3, 20, 3, 6));
+        assert!(lat.abs() < 0.6, "lat {}", lat);
+        let (lat, _) = subsolar_point(at(2024, 9, 22, 12, 44));
+        assert!(lat.abs() < 0.6, "lat {}", lat);
+    }
+
+    #[test]
+    fn test_subsolar_longitude_follows_clock() {
+        // Equation of time is close to zero in mid April and early September
+        let (_, lon) = subsolar_point(at(2024, 4, 15, 12, 0));
+        assert!(lon.abs() < 1.0, "lon {}", lon);
+        let (_, lon) = subsolar_point(at(2024, 4, 15, 18, 0));
+        assert!((lon + 90.0).abs() < 1.0, "lon {}", lon);
+        let (_, lon) = subsolar_point(at(2024, 4, 15, 0, 0));
+        assert!((lon.abs() - 180.0).abs() < 1.0, "lon {}", lon);
+    }
+
+    #[test]
+    fn test_equation_of_time_extremes() {
+        // Sun runs about 16 minutes fast in early November, 14 slow in February
+        let (_, eot) = solar_parameters(at(2024, 11, 3, 12, 0));
+        assert!((eot - 16.4).abs() < 0.5, "eot {}", eot);
+        let (_, eot) = solar_parameters(at(2024, 2, 11, 12, 0));
+        assert!((eot + 14.2).abs() < 0.5, "eot {}", eot);
+    }
+}
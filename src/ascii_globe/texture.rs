@@ -2,12 +2,89 @@ use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
 
-pub fn load_texture(filename: impl AsRef<Path>) -> Result<Vec<Vec<char>>> {
-    let content = fs::read_to_string(filename.as_ref())
-        .with_context(|| format!("Error loading texture: {:?}", filename.as_ref()))?;
-    
-    Ok(content
+/// Load a texture file: a cylindrical equal-area map with one line per row,
+/// north at the top and longitude -180° on the left. Rows are evenly spaced
+/// in the sine of latitude rather than in latitude, so an equirectangular
+/// image needs resampling first (`velocity texture` does this). Each
+/// character is an entry of `palette`, darkest first. Every row must be the
+/// same width.
+pub fn load_texture(filename: impl AsRef<Path>, palette: &[char]) -> Result<Vec<Vec<char>>> {
+    let filename = filename.as_ref();
+    let content = fs::read_to_string(filename)
+        .with_context(|| format!("Error loading texture: {:?}", filename))?;
+
+    let texture = parse_texture(&content, palette)
+        .with_context(|| format!("Invalid texture {:?}", filename))?;
+    Ok(texture)
+}
+
+fn parse_texture(content: &str, palette: &[char]) -> Result<Vec<Vec<char>>> {
+    let texture: Vec<Vec<char>> = content
         .lines()
         .map(|line| line.chars().collect())
-        .collect())
+        .collect();
+
+    let width = texture.first().map(|row| row.len()).unwrap_or(0);
+    if width == 0 {
+        anyhow::bail!("Texture is empty");
+    }
+
+    for (y, row) in texture.iter().enumerate() {
+        if row.len() != width {
+            anyhow::bail!(
+                "Row {} is {} characters wide but the first row is {}",
+                y + 1,
+                row.len(),
+                width
+            );
+        }
+        if let Some(x) = row.iter().position(|c| !palette.contains(c)) {
+            anyhow::bail!(
+                "Character {:?} at row {}, column {} isn't in the palette {:?}",
+                row[x],
+                y + 1,
+                x + 1,
+                palette.iter().collect::<String>()
+            );
+        }
+    }
+
+    Ok(texture)
+}
+
+/// Write `texture` in the format `load_texture` reads
+pub fn save_texture(filename: impl AsRef<Path>, texture: &[Vec<char>]) -> Result<()> {
+    let filename = filename.as_ref();
+    let mut content = String::with_capacity(texture.iter().map(|row| row.len() + 1).sum());
+    for row in texture {
+        content.extend(row.iter());
+        content.push('\n');
+    }
+    fs::write(filename, content).with_context(|| format!("Failed to write texture {:?}", filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [char; 3] = [' ', '.', '@'];
+
+    #[test]
+    fn test_parse_texture() {
+        let texture = parse_texture(" .@\r\n@. \r\n", &PALETTE).unwrap();
+        assert_eq!(texture, vec![vec![' ', '.', '@'], vec!['@', '.', ' ']]);
+    }
+
+    #[test]
+    fn test_parse_texture_rejects_ragged_rows() {
+        let err = parse_texture(" .@\n@.\n", &PALETTE).unwrap_err();
+        assert!(err.to_string().contains("Row 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_texture_rejects_unknown_characters() {
+        let err = parse_texture(" .@\n@x \n", &PALETTE).unwrap_err();
+        assert!(err.to_string().contains("'x' at row 2, column 2"), "{}", err);
+        assert!(parse_texture("", &PALETTE).is_err());
+    }
 }
@@ -0,0 +1,463 @@
+use crate::ascii_globe::camera::Cell;
+use crate::ascii_globe::{body, markers, GlobeRenderer};
+use crate::cli::{option_value, parse_option};
+use crate::config::{Config, LightSource, RenderMode};
+use crate::ui::color::{self, ColorSupport};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use ratatui::style::Color;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+Usage: velocity globe [OPTIONS]
+
+Render the globe to stdout, or export an animation of it turning.
+Unset options come from the [globe] section of the config file.
+
+Options:
+  --width N           Width in cells (default: terminal width, or 80)
+  --height N          Height in cells (default: terminal height, or 40)
+  --angle DEG         Longitude facing the viewer (default: 0)
+  --time TIME         Light the globe from the sun at an RFC 3339 time,
+                      e.g. 2024-06-21T12:00:00Z
+  --body NAME         Body to render, e.g. earth, moon, mars
+  --mode MODE         ascii, color, half_block or braille
+  --tilt DEG          Axial tilt
+  --scale X           Globe size
+  --frames N          Number of animation frames (default: 1)
+  --fps N             Frames per second for playback (default: 12)
+  --step DEG          Rotation per frame (default: one full turn over all frames)
+  --lapse SECONDS     Simulated time between frames (default: 0)
+  --format FORMAT     text, frames, asciicast or ansi (default: text)
+  --loops N           Times to play an ansi animation on a terminal, 0 for
+                      forever (default: 1)
+  --output PATH       File to write, or directory for --format frames
+  --no-color          Don't emit colour escapes (NO_COLOR does the same
+                      when writing to stdout)
+  -h, --help          Show this message
+
+Formats:
+  text       Frames one after another
+  frames     One file per frame in the --output directory
+  asciicast  asciinema v2 recording, playable with `asciinema play`
+  ansi       Frames that redraw in place; played in real time on a terminal";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Frames,
+    Asciicast,
+    Ansi,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GlobeArgs {
+    width: Option<usize>,
+    height: Option<usize>,
+    angle: f64,
+    time: Option<DateTime<Utc>>,
+    body: Option<String>,
+    mode: Option<RenderMode>,
+    tilt: Option<f64>,
+    scale: Option<f64>,
+    frames: usize,
+    fps: f64,
+    step: Option<f64>,
+    lapse: f64,
+    format: Format,
+    loops: usize,
+    output: Option<PathBuf>,
+    color: bool,
+    help: bool,
+}
+
+impl Default for GlobeArgs {
+    fn default() -> Self {
+        Self {
+            width: None,
+            height: None,
+            angle: 0.0,
+            time: None,
+            body: None,
+            mode: None,
+            tilt: None,
+            scale: None,
+            frames: 1,
+            fps: 12.0,
+            step: None,
+            lapse: 0.0,
+            format: Format::Text,
+            loops: 1,
+            output: None,
+            color: true,
+            help: false,
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<GlobeArgs> {
+    let mut parsed = GlobeArgs::default();
+    let mut values = args.iter();
+
+    while let Some(arg) = values.next() {
+        let name = arg.as_str();
+        match name {
+            "--width" => parsed.width = Some(parse_option(name, &mut values)?),
+            "--height" => parsed.height = Some(parse_option(name, &mut values)?),
+            "--angle" => parsed.angle = parse_option(name, &mut values)?,
+            "--time" => {
+                let value = option_value(name, &mut values)?;
+                let time = DateTime::parse_from_rfc3339(value)
+                    .with_context(|| format!("Invalid time '{}', expected e.g. 2024-06-21T12:00:00Z", value))?;
+                parsed.time = Some(time.with_timezone(&Utc));
+            }
+            "--body" => parsed.body = Some(option_value(name, &mut values)?.to_string()),
+            "--mode" => {
+                let value = option_value(name, &mut values)?;
+                let mode = RenderMode::deserialize(value.into_deserializer())
+                    .map_err(|e: serde::de::value::Error| anyhow::anyhow!("Invalid --mode '{}': {}", value, e))?;
+                parsed.mode = Some(mode);
+            }
+            "--tilt" => parsed.tilt = Some(parse_option(name, &mut values)?),
+            "--scale" => parsed.scale = Some(parse_option(name, &mut values)?),
+            "--frames" => parsed.frames = parse_option(name, &mut values)?,
+            "--fps" => parsed.fps = parse_option(name, &mut values)?,
+            "--step" => parsed.step = Some(parse_option(name, &mut values)?),
+            "--lapse" => parsed.lapse = parse_option(name, &mut values)?,
+            "--format" => {
+                parsed.format = match option_value(name, &mut values)? {
+                    "text" => Format::Text,
+                    "frames" => Format::Frames,
+                    "asciicast" => Format::Asciicast,
+                    "ansi" => Format::Ansi,
+                    other => anyhow::bail!("Unknown --format '{}'", other),
+                }
+            }
+            "--loops" => parsed.loops = parse_option(name, &mut values)?,
+            "--output" | "-o" => parsed.output = Some(PathBuf::from(option_value(name, &mut values)?)),
+            "--no-color" => parsed.color = false,
+            "-h" | "--help" => parsed.help = true,
+            other => anyhow::bail!("Unknown option '{}'\n\n{}", other, USAGE),
+        }
+    }
+
+    if parsed.frames == 0 {
+        anyhow::bail!("--frames must be at least 1");
+    }
+    if parsed.fps <= 0.0 {
+        anyhow::bail!("--fps must be positive");
+    }
+    if parsed.width == Some(0) || parsed.height == Some(0) {
+        anyhow::bail!("--width and --height must be at least 1");
+    }
+    if parsed.format == Format::Frames && parsed.output.is_none() {
+        anyhow::bail!("--format frames needs an --output directory");
+    }
+    Ok(parsed)
+}
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = parse_args(args)?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let config = Config::load()?;
+
+    let to_terminal = args.output.is_none() && io::stdout().is_terminal();
+    let (terminal_width, terminal_height) = if to_terminal {
+        crossterm::terminal::size()
+            .map(|(w, h)| (w as usize, h.saturating_sub(1).max(1) as usize))
+            .unwrap_or((80, 40))
+    } else {
+        (80, 40)
+    };
+    let width = args.width.unwrap_or(terminal_width);
+    let height = args.height.unwrap_or(terminal_height);
+
+    // Files are usually viewed somewhere else, so keep full colour for them
+    let colors = if !args.color {
+        None
+    } else if args.output.is_none() {
+        color::detect_colors()
+    } else {
+        Some(ColorSupport::TrueColor)
+    };
+    let mut renderer = build_renderer(&config, &args, colors)?;
+
+    let frames: Vec<Vec<String>> = render_frames(&mut renderer, &args, width, height)
+        .iter()
+        .map(|canvas| encode(canvas, colors))
+        .collect();
+
+    match args.format {
+        Format::Text => {
+            let text: Vec<String> = frames.iter().map(|lines| lines.join("\n")).collect();
+            write_output(&args, &(text.join("\n\n") + "\n"))
+        }
+        Format::Frames => {
+            let dir = args.output.as_ref().context("--format frames needs an --output directory")?;
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+            for (i, lines) in frames.iter().enumerate() {
+                let path = dir.join(format!("frame_{:04}.txt", i));
+                fs::write(&path, lines.join("\n") + "\n")
+                    .with_context(|| format!("Failed to write {:?}", path))?;
+            }
+            Ok(())
+        }
+        Format::Asciicast => write_output(&args, &asciicast(&frames, width, height, args.fps)),
+        Format::Ansi if to_terminal => play(&frames, args.fps, args.loops),
+        Format::Ansi => write_output(&args, &ansi_loop(&frames)),
+    }
+}
+
+fn build_renderer(config: &Config, args: &GlobeArgs, colors: Option<ColorSupport>) -> Result<GlobeRenderer> {
+    let globe = &config.globe;
+    let texture_dirs = body::texture_dirs(&globe.texture_path);
+    let body = body::find(&texture_dirs, args.body.as_deref().unwrap_or(&globe.body))?;
+
+    let mut renderer = GlobeRenderer::new(body);
+    renderer.set_scale(args.scale.unwrap_or(globe.scale));
+    renderer.set_tilt(args.tilt.unwrap_or(globe.tilt));
+    renderer.set_lighting(globe.lighting);
+    renderer.set_light_source(globe.light_source);
+    renderer.set_render_mode(color::mode_for_colors(args.mode.unwrap_or(globe.render_mode), colors));
+    let (globe_markers, globe_arcs) = markers::from_config(globe);
+    renderer.set_markers(globe_markers, globe_arcs);
+
+    if let Some(time) = args.time {
+        renderer.set_lighting(true);
+        renderer.set_light_source(LightSource::Sun);
+        renderer.set_time(time);
+    }
+    Ok(renderer)
+}
+
+fn render_frames(renderer: &mut GlobeRenderer, args: &GlobeArgs, width: usize, height: usize) -> Vec<Vec<Vec<Cell>>> {
+    let step = args.step.unwrap_or(360.0 / args.frames as f64);
+    (0..args.frames)
+        .map(|i| {
+            renderer.set_center_longitude(args.angle + step * i as f64);
+            if let Some(time) = args.time {
+                let offset = Duration::milliseconds((args.lapse * i as f64 * 1000.0) as i64);
+                renderer.set_time(time + offset);
+            }
+            let mut canvas = vec![vec![Cell::BLANK; width]; height];
+            renderer.render_frame(&mut canvas, width, height);
+            canvas
+        })
+        .collect()
+}
+
+/// Lines of text for `canvas`, with SGR colour escapes when `colors` is set
+fn encode(canvas: &[Vec<Cell>], colors: Option<ColorSupport>) -> Vec<String> {
+    canvas
+        .iter()
+        .map(|row| {
+            let mut line = String::new();
+            let mut current = String::new();
+            for cell in row {
+                if let Some(support) = colors {
+                    let mut style = String::new();
+                    if let Some(fg) = cell.fg {
+                        style.push(';');
+                        style.push_str(&sgr(support.quantize(fg), false));
+                    }
+                    if let Some(bg) = cell.bg {
+                        style.push(';');
+                        style.push_str(&sgr(support.quantize(bg), true));
+                    }
+                    if style != current {
+                        line.push_str(&format!("\x1b[0{}m", style));
+                        current = style;
+                    }
+                }
+                line.push(cell.ch);
+            }
+            if !current.is_empty() {
+                line.push_str("\x1b[0m");
+            }
+            line
+        })
+        .collect()
+}
+
+/// SGR parameters selecting `color` as the foreground or background
+fn sgr(color: Color, background: bool) -> String {
+    let base = if background { 40 } else { 30 };
+    match color {
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        Color::Indexed(i) => format!("{};5;{}", base + 8, i),
+        named => {
+            let offset = match named {
+                Color::Black => 0,
+                Color::Red => 1,
+                Color::Green => 2,
+                Color::Yellow => 3,
+                Color::Blue => 4,
+                Color::Magenta => 5,
+                Color::Cyan => 6,
+                Color::Gray => 7,
+                Color::DarkGray => 60,
+                Color::LightRed => 61,
+                Color::LightGreen => 62,
+                Color::LightYellow => 63,
+                Color::LightBlue => 64,
+                Color::LightMagenta => 65,
+                Color::LightCyan => 66,
+                _ => 67,
+            };
+            (base + offset).to_string()
+        }
+    }
+}
+
+/// asciinema v2 recording: a JSON header line, then one `[time, "o", data]`
+/// event per frame
+fn asciicast(frames: &[Vec<String>], width: usize, height: usize, fps: f64) -> String {
+    let header = serde_json::json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "env": { "TERM": "xterm-256color" },
+    });
+    let mut out = header.to_string();
+    out.push('\n');
+    for (i, lines) in frames.iter().enumerate() {
+        let clear = if i == 0 { "\x1b[2J" } else { "" };
+        let data = format!("{}\x1b[H{}", clear, lines.join("\r\n"));
+        let event = serde_json::json!([i as f64 / fps, "o", data]);
+        out.push_str(&event.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/// Frames back to back, each moving the cursor up to redraw over the last
+fn ansi_loop(frames: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    for (i, lines) in frames.iter().enumerate() {
+        if i > 0 {
+            out.push_str(&rewind(lines.len()));
+        }
+        out.push_str(&lines.join("\n"));
+    }
+    out.push('\n');
+    out
+}
+
+/// Move the cursor from the last line of a `height` line frame to its start
+fn rewind(height: usize) -> String {
+    if height > 1 {
+        format!("\r\x1b[{}A", height - 1)
+    } else {
+        "\r".to_string()
+    }
+}
+
+/// Play frames on the terminal in real time, `loops` times or forever if 0
+fn play(frames: &[Vec<String>], fps: f64, loops: usize) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let delay = std::time::Duration::from_secs_f64(1.0 / fps);
+    let mut first = true;
+    let mut played = 0;
+    while loops == 0 || played < loops {
+        for lines in frames {
+            if !first {
+                write!(stdout, "{}", rewind(lines.len()))?;
+            }
+            first = false;
+            write!(stdout, "{}", lines.join("\n"))?;
+            stdout.flush()?;
+            std::thread::sleep(delay);
+        }
+        played += 1;
+    }
+    writeln!(stdout)?;
+    Ok(())
+}
+
+fn write_output(args: &GlobeArgs, content: &str) -> Result<()> {
+    match &args.output {
+        Some(path) => fs::write(path, content).with_context(|| format!("Failed to write {:?}", path)),
+        None => {
+            io::stdout().write_all(content.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<GlobeArgs> {
+        let list: Vec<String> = list.iter().map(|s| s.to_string()).collect();
+        parse_args(&list)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(args(&[]).unwrap(), GlobeArgs::default());
+
+        let parsed = args(&[
+            "--width", "60", "--angle", "-45.5", "--time", "2024-06-21T12:00:00+02:00",
+            "--mode", "half_block", "--frames", "24", "--format", "asciicast", "-o", "out.cast",
+        ])
+        .unwrap();
+        assert_eq!(parsed.width, Some(60));
+        assert_eq!(parsed.angle, -45.5);
+        assert_eq!(parsed.time.unwrap().to_rfc3339(), "2024-06-21T10:00:00+00:00");
+        assert_eq!(parsed.mode, Some(RenderMode::HalfBlock));
+        assert_eq!(parsed.frames, 24);
+        assert_eq!(parsed.format, Format::Asciicast);
+        assert_eq!(parsed.output, Some(PathBuf::from("out.cast")));
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(args(&["--width"]).is_err());
+        assert!(args(&["--width", "wide"]).is_err());
+        assert!(args(&["--mode", "sparkly"]).is_err());
+        assert!(args(&["--frames", "0"]).is_err());
+        assert!(args(&["--format", "frames"]).is_err());
+        assert!(args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_encode_colors() {
+        let red = Cell { ch: '@', fg: Some([255, 0, 0]), bg: None };
+        let canvas = vec![vec![Cell::BLANK, red, red, Cell::BLANK]];
+        assert_eq!(encode(&canvas, None), vec![" @@ "]);
+        assert_eq!(
+            encode(&canvas, Some(ColorSupport::TrueColor)),
+            vec![" \x1b[0;38;2;255;0;0m@@\x1b[0m "]
+        );
+        assert_eq!(
+            encode(&canvas, Some(ColorSupport::Ansi16))[0],
+            " \x1b[0;91m@@\x1b[0m "
+        );
+    }
+
+    #[test]
+    fn test_asciicast() {
+        let frames = vec![vec!["ab".to_string(), "cd".to_string()]; 3];
+        let cast = asciicast(&frames, 2, 2, 10.0);
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines.len(), 4);
+
+        let header: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 2);
+
+        let event: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(event[0], 0.1);
+        assert_eq!(event[1], "o");
+        assert_eq!(event[2], "\x1b[Hab\r\ncd");
+    }
+}
@@ -0,0 +1,52 @@
+pub mod globe;
+pub mod texture;
+
+use anyhow::Result;
+
+const USAGE: &str = "\
+Usage: velocity [COMMAND]
+
+Starts the launcher when no command is given.
+
+Commands:
+  globe    Render the globe to stdout or export an animation
+  texture  Convert a PGM/PPM image into a globe texture
+  help     Show this message
+
+Run `velocity <COMMAND> --help` for command options.";
+
+/// Run the subcommand named by `args` (without the program name). Returns
+/// `Ok(false)` when there is none and the TUI should start.
+pub fn run(args: &[String]) -> Result<bool> {
+    let Some(command) = args.first() else {
+        return Ok(false);
+    };
+
+    match command.as_str() {
+        "globe" => globe::run(&args[1..])?,
+        "texture" => texture::run(&args[1..])?,
+        "help" | "-h" | "--help" => println!("{}", USAGE),
+        other => anyhow::bail!("Unknown command '{}'\n\n{}", other, USAGE),
+    }
+    Ok(true)
+}
+
+/// Value following option `name`, e.g. the `80` in `--width 80`
+fn option_value<'a>(name: &str, values: &mut impl Iterator<Item = &'a String>) -> Result<&'a str> {
+    values
+        .next()
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("{} needs a value", name))
+}
+
+/// Parse the value of option `name`
+fn parse_option<'a, T>(name: &str, values: &mut impl Iterator<Item = &'a String>) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = option_value(name, values)?;
+    value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid value '{}' for {}: {}", value, name, e))
+}
@@ -0,0 +1,70 @@
+use crate::ascii_globe::convert::{self, ConvertOptions};
+use crate::cli::{option_value, parse_option};
+use crate::config::Config;
+use anyhow::Result;
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+Usage: velocity texture <DAY_IMAGE> [OPTIONS]
+
+Convert an equirectangular PGM/PPM image into a globe texture and manifest.
+The image should have north at the top and longitude -180 on the left; its
+rows are resampled to the equal-area layout the globe draws from.
+
+Options:
+  --night IMAGE       Image for the unlit side, e.g. city lights
+  --name NAME         Body name (default: the image's file name)
+  --width N           Texture width in characters (default: 203)
+  --height N          Texture height in characters (default: 80)
+  --palette CHARS     Characters from darkest to brightest
+  --output DIR        Where to write the files
+                      (default: textures/ in the config directory)
+  -h, --help          Show this message";
+
+pub fn run(args: &[String]) -> Result<()> {
+    let mut day: Option<PathBuf> = None;
+    let mut night = None;
+    let mut name = None;
+    let mut width = 203;
+    let mut height = 80;
+    let mut palette = None;
+    let mut output = None;
+
+    let mut values = args.iter();
+    while let Some(arg) = values.next() {
+        let option = arg.as_str();
+        match option {
+            "--night" => night = Some(PathBuf::from(option_value(option, &mut values)?)),
+            "--name" => name = Some(option_value(option, &mut values)?.to_string()),
+            "--width" => width = parse_option(option, &mut values)?,
+            "--height" => height = parse_option(option, &mut values)?,
+            "--palette" => palette = Some(option_value(option, &mut values)?.to_string()),
+            "--output" | "-o" => output = Some(PathBuf::from(option_value(option, &mut values)?)),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            other if other.starts_with('-') => anyhow::bail!("Unknown option '{}'\n\n{}", other, USAGE),
+            path if day.is_none() => day = Some(PathBuf::from(path)),
+            extra => anyhow::bail!("Unexpected argument '{}'\n\n{}", extra, USAGE),
+        }
+    }
+
+    let Some(day) = day else {
+        anyhow::bail!("Missing day image\n\n{}", USAGE);
+    };
+    let name = name.unwrap_or_else(|| {
+        day.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Custom".to_string())
+    });
+    let output = match output {
+        Some(dir) => dir,
+        None => Config::config_dir()?.join("textures"),
+    };
+
+    let options = ConvertOptions { name, width, height, palette };
+    let manifest = convert::convert(&day, night.as_deref(), &output, &options)?;
+    println!("Wrote {}", manifest.display());
+    Ok(())
+}
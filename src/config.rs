@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use dirs;
@@ -11,14 +12,51 @@ pub struct Config {
     pub ui: UiConfig,
     #[serde(default = "default_theme")]
     pub theme: ThemeConfig,
+    #[serde(default)]
+    pub stars: StarsConfig,
+    #[serde(default)]
+    pub keys: KeysConfig,
+    #[serde(default)]
+    pub recent: RecentConfig,
+    /// Commands for opening files, tried in order; unmatched files go to
+    /// the system opener
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub openers: Vec<OpenerConfig>,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+    /// Add installed applications from their `.desktop` files after `apps`
+    #[serde(default)]
+    pub discover_apps: bool,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct AppEntry {
     pub name: String,
     pub command: String,
     #[serde(default)]
     pub args: Option<Vec<String>>,
+    /// Tab the app is listed under in the Apps view, besides "All"
+    #[serde(default, alias = "category", skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Listed first, before the most used apps
+    #[serde(default, alias = "favorite", skip_serializing_if = "is_false")]
+    pub favourite: bool,
+}
+
+/// Opens files matching `extensions` or `globs` with `command`, in which
+/// `{path}`, `{dir}` and `{line}` are replaced; without `{path}` the path
+/// is added as the last argument
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OpenerConfig {
+    pub name: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub globs: Vec<String>,
+    pub command: String,
+    /// Run the command in a new terminal window, for terminal editors
+    #[serde(default)]
+    pub terminal: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -33,6 +71,292 @@ pub struct GlobeConfig {
     pub lighting: bool,
     #[serde(default = "default_texture_path")]
     pub texture_path: String,
+    #[serde(default = "default_body")]
+    pub body: String,
+    #[serde(default)]
+    pub light_source: LightSource,
+    #[serde(default = "default_time_lapse")]
+    pub time_lapse: f64,
+    #[serde(default)]
+    pub render_mode: RenderMode,
+    #[serde(default)]
+    pub markers: Vec<MarkerConfig>,
+    #[serde(default)]
+    pub arcs: Vec<ArcConfig>,
+    /// Add a marker for `home`, or the system timezone's city if unset
+    #[serde(default)]
+    pub home_marker: bool,
+    #[serde(default)]
+    pub home: Option<HomeLocation>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MarkerConfig {
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default = "default_marker_glyph")]
+    pub glyph: char,
+    #[serde(default = "default_marker_color")]
+    pub color: [u8; 3],
+}
+
+/// Great-circle arc between two markers, referenced by label
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ArcConfig {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "default_arc_color")]
+    pub color: [u8; 3],
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HomeLocation {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default = "default_home_label")]
+    pub label: String,
+}
+
+/// How the globe is drawn into terminal cells
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// Monochrome palette characters
+    #[default]
+    Ascii,
+    /// Palette characters coloured from the body's colour ramps
+    Color,
+    /// Coloured half blocks, two samples per cell
+    HalfBlock,
+    /// Braille dots, 2x4 samples per cell
+    Braille,
+}
+
+/// Where the globe's light comes from when `lighting` is on
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LightSource {
+    /// Fixed light to one side of the globe
+    #[default]
+    Fixed,
+    /// Real sun position computed from the system clock
+    Sun,
+}
+
+/// Key bindings as action name -> keys, e.g. `quit = ["q", "ctrl+c"]`.
+/// Keys pressed in sequence are space-separated (`"g g"`). The per-view
+/// sections replace `global` bindings for the actions they list.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct KeysConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub global: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub home: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub apps: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recent: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub settings: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, Vec<String>>,
+}
+
+/// Other programs' histories merged into the recent files list
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RecentConfig {
+    /// `~/.local/share/recently-used.xbel`, written by GTK and other desktop apps
+    #[serde(default = "default_true")]
+    pub import_xbel: bool,
+    /// Files opened with an editor in bash or zsh history
+    #[serde(default)]
+    pub import_shell_history: bool,
+    /// Vim's file list in `~/.viminfo`
+    #[serde(default)]
+    pub import_editor_history: bool,
+    /// Most files kept, not counting pinned ones
+    #[serde(default = "default_max_recent_files")]
+    pub max_files: usize,
+    #[serde(default)]
+    pub ranking: RecentRanking,
+    /// Show the selected file beside the list when there's room
+    #[serde(default = "default_true")]
+    pub preview: bool,
+    #[serde(default = "default_preview_lines")]
+    pub preview_lines: usize,
+    /// Which files are tracked
+    #[serde(default)]
+    pub filter: FilterConfig,
+}
+
+/// Include and exclude rules for recent files. Exclusions win. Globs
+/// without a `/` match the file name, others the whole path; `**` crosses
+/// directories.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FilterConfig {
+    #[serde(default = "default_filter_extensions")]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub exclude_extensions: Vec<String>,
+    #[serde(default = "default_filter_globs")]
+    pub globs: Vec<String>,
+    #[serde(default = "default_exclude_globs")]
+    pub exclude_globs: Vec<String>,
+    /// MIME types, e.g. `text/*`, accepted for files without an extension
+    #[serde(default = "default_filter_mime_types")]
+    pub mime_types: Vec<String>,
+    /// Read the first bytes of extensionless files to find their type
+    #[serde(default = "default_true")]
+    pub sniff_mime: bool,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            extensions: default_filter_extensions(),
+            exclude_extensions: Vec::new(),
+            globs: default_filter_globs(),
+            exclude_globs: default_exclude_globs(),
+            mime_types: default_filter_mime_types(),
+            sniff_mime: true,
+        }
+    }
+}
+
+/// How the recent files list is ordered. Pinned files always come first.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentRanking {
+    /// Open count weighted by how recently the file was opened
+    #[default]
+    Frecency,
+    /// Most recently opened first
+    Recency,
+}
+
+impl Default for RecentConfig {
+    fn default() -> Self {
+        Self {
+            import_xbel: true,
+            import_shell_history: false,
+            import_editor_history: false,
+            max_files: default_max_recent_files(),
+            ranking: RecentRanking::default(),
+            preview: true,
+            preview_lines: default_preview_lines(),
+            filter: FilterConfig::default(),
+        }
+    }
+}
+
+/// Where the Projects view looks for git repositories and what it can run
+/// in one
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ProjectsConfig {
+    /// Directories searched for repositories; `~/` is the home directory
+    #[serde(default = "default_project_roots")]
+    pub roots: Vec<String>,
+    /// How many directories below a root a repository can be
+    #[serde(default = "default_project_depth")]
+    pub max_depth: usize,
+    /// Commands offered for a project. Launching one runs every command
+    /// marked `on_launch`, or the first if none are.
+    #[serde(default = "default_project_commands")]
+    pub commands: Vec<ProjectCommand>,
+}
+
+/// A command run in a project's directory. `{dir}`, `{name}` and
+/// `{editor}` (the `ui.text_editor`) are replaced; a terminal command left
+/// empty just opens the terminal there.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ProjectCommand {
+    pub name: String,
+    #[serde(default)]
+    pub command: String,
+    /// Run the command in a new terminal window
+    #[serde(default)]
+    pub terminal: bool,
+    /// Run the command when a project is launched from the list
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub on_launch: bool,
+}
+
+impl ProjectsConfig {
+    /// The commands run when a project is launched from the list
+    pub fn launch_commands(&self) -> Vec<&ProjectCommand> {
+        let marked: Vec<&ProjectCommand> = self.commands.iter().filter(|c| c.on_launch).collect();
+        if marked.is_empty() {
+            self.commands.iter().take(1).collect()
+        } else {
+            marked
+        }
+    }
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            roots: default_project_roots(),
+            max_depth: default_project_depth(),
+            commands: default_project_commands(),
+        }
+    }
+}
+
+/// Background starfield behind the menus
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StarsConfig {
+    /// Fixed seed for the sky; if unset one is picked on first run and
+    /// kept in `sky_seed` so the same sky comes back every start
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Background stars per terminal cell
+    #[serde(default = "default_star_density")]
+    pub density: f64,
+    /// Draw real constellations from the bundled catalogue
+    #[serde(default = "default_true")]
+    pub constellations: bool,
+    #[serde(default = "default_true")]
+    pub shooting_stars: bool,
+    /// How far the sky drifts per globe revolution, as a fraction of the
+    /// screen width; 0 keeps it still
+    #[serde(default = "default_parallax")]
+    pub parallax: f64,
+}
+
+impl Default for StarsConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            density: default_star_density(),
+            constellations: true,
+            shooting_stars: true,
+            parallax: default_parallax(),
+        }
+    }
+}
+
+impl StarsConfig {
+    /// The configured seed, or the one saved on an earlier run. A new seed
+    /// is saved if there's none yet; if that fails the sky is just random.
+    pub fn resolve_seed(&self) -> u64 {
+        if let Some(seed) = self.seed {
+            return seed;
+        }
+        let Ok(path) = Config::config_dir().map(|dir| dir.join("sky_seed")) else {
+            return rand::random();
+        };
+        if let Some(seed) = fs::read_to_string(&path).ok().and_then(|s| s.trim().parse().ok()) {
+            return seed;
+        }
+        let seed: u64 = rand::random();
+        let _ = fs::create_dir_all(path.parent().unwrap_or(&path))
+            .and_then(|_| fs::write(&path, seed.to_string()));
+        seed
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -43,40 +367,44 @@ pub struct UiConfig {
     pub text_editor: String,
     #[serde(default = "default_text_dir")]
     pub default_text_dir: String,
+    /// Name of a bundled theme or a file in `themes/`; the `[theme]`
+    /// colours are used if unset
+    #[serde(default)]
+    pub theme: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ThemeConfig {
-    #[serde(default = "default_text_primary")]
+    #[serde(default = "default_text_primary", deserialize_with = "deserialize_rgb")]
     pub text_primary: [u8; 3],
-    #[serde(default = "default_text_secondary")]
+    #[serde(default = "default_text_secondary", deserialize_with = "deserialize_rgb")]
     pub text_secondary: [u8; 3],
-    #[serde(default = "default_text_selected")]
+    #[serde(default = "default_text_selected", deserialize_with = "deserialize_rgb")]
     pub text_selected: [u8; 3],
-    #[serde(default = "default_text_accent")]
+    #[serde(default = "default_text_accent", deserialize_with = "deserialize_rgb")]
     pub text_accent: [u8; 3],
     
-    #[serde(default = "default_star_dim")]
+    #[serde(default = "default_star_dim", deserialize_with = "deserialize_rgb")]
     pub star_dim: [u8; 3],
-    #[serde(default = "default_star_medium")]
+    #[serde(default = "default_star_medium", deserialize_with = "deserialize_rgb")]
     pub star_medium: [u8; 3],
-    #[serde(default = "default_star_light")]
+    #[serde(default = "default_star_light", deserialize_with = "deserialize_rgb")]
     pub star_light: [u8; 3],
-    #[serde(default = "default_star_bright")]
+    #[serde(default = "default_star_bright", deserialize_with = "deserialize_rgb")]
     pub star_bright: [u8; 3],
-    #[serde(default = "default_star_brightest")]
+    #[serde(default = "default_star_brightest", deserialize_with = "deserialize_rgb")]
     pub star_brightest: [u8; 3],
     
-    #[serde(default = "default_status_good")]
+    #[serde(default = "default_status_good", deserialize_with = "deserialize_rgb")]
     pub status_good: [u8; 3],
-    #[serde(default = "default_status_warning")]
+    #[serde(default = "default_status_warning", deserialize_with = "deserialize_rgb")]
     pub status_warning: [u8; 3],
-    #[serde(default = "default_status_error")]
+    #[serde(default = "default_status_error", deserialize_with = "deserialize_rgb")]
     pub status_error: [u8; 3],
-    #[serde(default = "default_status_info")]
+    #[serde(default = "default_status_info", deserialize_with = "deserialize_rgb")]
     pub status_info: [u8; 3],
     
-    #[serde(default = "default_border")]
+    #[serde(default = "default_border", deserialize_with = "deserialize_rgb")]
     pub border: [u8; 3],
 }
 
@@ -96,6 +424,83 @@ fn default_lighting() -> bool {
     false
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_recent_files() -> usize {
+    20
+}
+
+fn default_preview_lines() -> usize {
+    40
+}
+
+fn default_filter_extensions() -> Vec<String> {
+    [
+        "txt", "md", "rs", "py", "js", "ts", "json", "toml", "yaml", "yml",
+        "xml", "html", "css", "sh", "zsh", "bash", "c", "cpp", "h", "hpp",
+        "java", "go", "rb", "php", "swift", "kt", "scala", "clj", "lua",
+        "vim", "conf", "config", "ini", "log", "csv", "tsv",
+    ]
+    .iter()
+    .map(|e| e.to_string())
+    .collect()
+}
+
+// Well-known files with no extension, so they're found without sniffing
+fn default_filter_globs() -> Vec<String> {
+    ["Makefile", "GNUmakefile", "Dockerfile", "Containerfile", "Justfile"]
+        .iter()
+        .map(|g| g.to_string())
+        .collect()
+}
+
+fn default_exclude_globs() -> Vec<String> {
+    vec!["**/.git/**".to_string()]
+}
+
+fn default_filter_mime_types() -> Vec<String> {
+    vec!["text/*".to_string()]
+}
+
+fn default_project_roots() -> Vec<String> {
+    ["~/src", "~/projects", "~/code", "~/dev"].iter().map(|s| s.to_string()).collect()
+}
+
+fn default_project_depth() -> usize {
+    3
+}
+
+fn default_project_commands() -> Vec<ProjectCommand> {
+    vec![
+        ProjectCommand {
+            name: "Editor".to_string(),
+            command: "{editor} {dir}".to_string(),
+            terminal: false,
+            on_launch: true,
+        },
+        ProjectCommand {
+            name: "Terminal".to_string(),
+            command: String::new(),
+            terminal: true,
+            on_launch: true,
+        },
+    ]
+}
+
+fn default_star_density() -> f64 {
+    0.05
+}
+
+fn default_parallax() -> f64 {
+    0.25
+}
+
 fn default_fps() -> u32 {
     60
 }
@@ -123,6 +528,30 @@ fn default_texture_path() -> String {
     "textures".to_string()
 }
 
+fn default_body() -> String {
+    "earth".to_string()
+}
+
+fn default_time_lapse() -> f64 {
+    1.0
+}
+
+fn default_marker_glyph() -> char {
+    '●'
+}
+
+fn default_marker_color() -> [u8; 3] {
+    [255, 90, 90]
+}
+
+fn default_arc_color() -> [u8; 3] {
+    [255, 200, 80]
+}
+
+fn default_home_label() -> String {
+    "Home".to_string()
+}
+
 fn default_text_primary() -> [u8; 3] {
     [255, 255, 255]
 }
@@ -179,6 +608,34 @@ fn default_border() -> [u8; 3] {
     [255, 255, 255]
 }
 
+/// Read a colour written either as `[r, g, b]` or as a `"#rrggbb"` string
+fn deserialize_rgb<'de, D>(deserializer: D) -> Result<[u8; 3], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Rgb {
+        Array([u8; 3]),
+        Hex(String),
+    }
+
+    match Rgb::deserialize(deserializer)? {
+        Rgb::Array(rgb) => Ok(rgb),
+        Rgb::Hex(hex) => parse_hex_color(&hex).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Parse `"#rrggbb"` (the `#` is optional)
+fn parse_hex_color(hex: &str) -> Result<[u8; 3]> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid colour {:?}, expected \"#rrggbb\"", hex);
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or(0);
+    Ok([channel(0), channel(2), channel(4)])
+}
+
 fn default_theme() -> ThemeConfig {
     ThemeConfig {
         text_primary: default_text_primary(),
@@ -206,91 +663,109 @@ impl Default for Config {
                     name: "Terminal".to_string(),
                     command: "open".to_string(),
                     args: Some(vec!["-a".to_string(), "Terminal.app".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "VS Code".to_string(),
                     command: "code".to_string(),
                     args: None,
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "Finder".to_string(),
                     command: "open".to_string(),
                     args: Some(vec!["-a".to_string(), "Finder.app".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 4".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 4".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 5".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 5".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "Application 6".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["Application 6".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 7".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 7".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 8".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 8".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 9".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 9".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 10".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 10".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 11".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 11".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 12".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 12".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 13".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 13".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 14".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 14".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 15".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 15".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 16".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 16".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 17".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 17".to_string()]),
+                    ..Default::default()
                 },
                 AppEntry {
                     name: "App 18".to_string(),
                     command: "echo".to_string(),
                     args: Some(vec!["App 18".to_string()]),
+                    ..Default::default()
                 },
             ],
             globe: GlobeConfig {
@@ -299,13 +774,28 @@ impl Default for Config {
                 tilt: 23.5,
                 lighting: false,
                 texture_path: default_texture_path(),
+                body: default_body(),
+                light_source: LightSource::Fixed,
+                time_lapse: default_time_lapse(),
+                render_mode: RenderMode::Ascii,
+                markers: Vec::new(),
+                arcs: Vec::new(),
+                home_marker: false,
+                home: None,
             },
             ui: UiConfig {
                 target_fps: 60,
                 text_editor: default_text_editor(),
                 default_text_dir: default_text_dir(),
+                theme: None,
             },
             theme: default_theme(),
+            stars: StarsConfig::default(),
+            keys: KeysConfig::default(),
+            recent: RecentConfig::default(),
+            openers: Vec::new(),
+            projects: ProjectsConfig::default(),
+            discover_apps: false,
         }
     }
 }
@@ -339,26 +829,30 @@ impl Config {
         
         // TEMPORARY: Add fake apps for testing the 15-position layout
         let mut fake_apps = vec![
-            AppEntry { name: "App 4".to_string(), command: "echo".to_string(), args: Some(vec!["App 4".to_string()]) },
-            AppEntry { name: "App 5".to_string(), command: "echo".to_string(), args: Some(vec!["App 5".to_string()]) },
-            AppEntry { name: "Application 6".to_string(), command: "echo".to_string(), args: Some(vec!["Application 6".to_string()]) },
-            AppEntry { name: "App 7".to_string(), command: "echo".to_string(), args: Some(vec!["App 7".to_string()]) },
-            AppEntry { name: "App 8".to_string(), command: "echo".to_string(), args: Some(vec!["App 8".to_string()]) },
-            AppEntry { name: "App 9".to_string(), command: "echo".to_string(), args: Some(vec!["App 9".to_string()]) },
-            AppEntry { name: "App 10".to_string(), command: "echo".to_string(), args: Some(vec!["App 10".to_string()]) },
-            AppEntry { name: "App 11".to_string(), command: "echo".to_string(), args: Some(vec!["App 11".to_string()]) },
-            AppEntry { name: "App 12".to_string(), command: "echo".to_string(), args: Some(vec!["App 12".to_string()]) },
-            AppEntry { name: "App 13".to_string(), command: "echo".to_string(), args: Some(vec!["App 13".to_string()]) },
-            AppEntry { name: "App 14".to_string(), command: "echo".to_string(), args: Some(vec!["App 14".to_string()]) },
-            AppEntry { name: "App 15".to_string(), command: "echo".to_string(), args: Some(vec!["App 15".to_string()]) },
-            AppEntry { name: "App 16".to_string(), command: "echo".to_string(), args: Some(vec!["App 16".to_string()]) },
-            AppEntry { name: "App 17".to_string(), command: "echo".to_string(), args: Some(vec!["App 17".to_string()]) },
-            AppEntry { name: "App 18".to_string(), command: "echo".to_string(), args: Some(vec!["App 18".to_string()]) },
+            AppEntry { name: "App 4".to_string(), command: "echo".to_string(), args: Some(vec!["App 4".to_string()]), ..Default::default() },
+            AppEntry { name: "App 5".to_string(), command: "echo".to_string(), args: Some(vec!["App 5".to_string()]), ..Default::default() },
+            AppEntry { name: "Application 6".to_string(), command: "echo".to_string(), args: Some(vec!["Application 6".to_string()]), ..Default::default() },
+            AppEntry { name: "App 7".to_string(), command: "echo".to_string(), args: Some(vec!["App 7".to_string()]), ..Default::default() },
+            AppEntry { name: "App 8".to_string(), command: "echo".to_string(), args: Some(vec!["App 8".to_string()]), ..Default::default() },
+            AppEntry { name: "App 9".to_string(), command: "echo".to_string(), args: Some(vec!["App 9".to_string()]), ..Default::default() },
+            AppEntry { name: "App 10".to_string(), command: "echo".to_string(), args: Some(vec!["App 10".to_string()]), ..Default::default() },
+            AppEntry { name: "App 11".to_string(), command: "echo".to_string(), args: Some(vec!["App 11".to_string()]), ..Default::default() },
+            AppEntry { name: "App 12".to_string(), command: "echo".to_string(), args: Some(vec!["App 12".to_string()]), ..Default::default() },
+            AppEntry { name: "App 13".to_string(), command: "echo".to_string(), args: Some(vec!["App 13".to_string()]), ..Default::default() },
+            AppEntry { name: "App 14".to_string(), command: "echo".to_string(), args: Some(vec!["App 14".to_string()]), ..Default::default() },
+            AppEntry { name: "App 15".to_string(), command: "echo".to_string(), args: Some(vec!["App 15".to_string()]), ..Default::default() },
+            AppEntry { name: "App 16".to_string(), command: "echo".to_string(), args: Some(vec!["App 16".to_string()]), ..Default::default() },
+            AppEntry { name: "App 17".to_string(), command: "echo".to_string(), args: Some(vec!["App 17".to_string()]), ..Default::default() },
+            AppEntry { name: "App 18".to_string(), command: "echo".to_string(), args: Some(vec!["App 18".to_string()]), ..Default::default() },
         ];
         config.apps.append(&mut fake_apps);
+        if config.discover_apps {
+            config.apps.extend(crate::apps::discover_apps());
+        }
         
         // Deduplicate apps by name - keep only first occurrence of each app name
-        // Do this AFTER appending fake apps so we catch all duplicates
+        // Do this AFTER appending fake and discovered apps so we catch all
+        // duplicates, and configured apps win over discovered ones
         let mut seen_names = std::collections::HashSet::new();
         config.apps.retain(|app| {
             if seen_names.contains(&app.name) {
@@ -381,9 +875,54 @@ impl Config {
         let content = toml::to_string_pretty(self)
             .context("Failed to serialize config")?;
         
-        fs::write(&config_path, content)
-            .with_context(|| format!("Failed to write config to {:?}", config_path))?;
-        
-        Ok(())
+        // Atomically, so a crash or a second instance never leaves half a config
+        crate::persist::with_lock(&config_path, || crate::persist::write_atomic(&config_path, content.as_bytes()))
+            .with_context(|| format!("Failed to write config to {:?}", config_path))
+    }
+
+    /// Set `ui.theme` in the config file, keeping the rest of it, comments
+    /// and formatting included, as it is. `save` would also write out the
+    /// apps `load` adds.
+    pub fn save_theme(theme: &str) -> Result<()> {
+        let config_path = Self::config_path()?;
+        crate::persist::with_lock(&config_path, || {
+            let content = match fs::read_to_string(&config_path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e).with_context(|| format!("Failed to read config from {:?}", config_path)),
+            };
+            let content = with_theme(&content, theme)
+                .with_context(|| format!("Failed to update config {:?}", config_path))?;
+            crate::persist::write_atomic(&config_path, content.as_bytes())
+        })
+        .with_context(|| format!("Failed to write config to {:?}", config_path))
+    }
+}
+
+/// `content` with `ui.theme` set to `theme`
+fn with_theme(content: &str, theme: &str) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = content.parse().context("Failed to parse config")?;
+    let ui = document
+        .entry("ui")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .context("`ui` is not a table")?;
+    ui.insert("theme", toml_edit::value(theme));
+    Ok(document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_theme_keeps_formatting() {
+        let content = "# My setup\n[ui]\ntarget_fps = 30 # smooth enough\ntheme = \"nord\"\n\n[globe]\nscale = 1.0\n";
+        assert_eq!(
+            with_theme(content, "dracula").unwrap(),
+            "# My setup\n[ui]\ntarget_fps = 30 # smooth enough\ntheme = \"dracula\"\n\n[globe]\nscale = 1.0\n"
+        );
+        assert_eq!(with_theme("", "nord").unwrap(), "[ui]\ntheme = \"nord\"\n");
+        assert!(with_theme("ui = 3\n", "nord").is_err());
     }
 }
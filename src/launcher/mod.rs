@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use crate::config::{AppEntry, OpenerConfig, ProjectCommand};
+use std::process::{Command, Stdio};
+use std::path::Path;
+use std::fs;
+use std::io::Write;
+
+mod opener;
+pub mod template;
+
+pub use opener::split_command;
+
+pub fn launch_app(app: &AppEntry) -> Result<()> {
+    let mut cmd = Command::new(&app.command);
+    
+    if let Some(ref args) = app.args {
+        cmd.args(args);
+    }
+    
+    // Launch in background, detached from terminal
+    cmd.stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::null());
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0); // Create new process group
+    }
+    
+    cmd.spawn()
+        .with_context(|| format!("Failed to launch {}", app.name))?;
+    
+    Ok(())
+}
+
+pub fn launch_terminal() -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        let app = AppEntry {
+            name: "Terminal".to_string(),
+            command: "open".to_string(),
+            args: Some(vec!["-a".to_string(), "Terminal.app".to_string()]),
+            ..Default::default()
+        };
+        launch_app(&app)
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Try common terminal emulators
+        let terminals = ["xterm", "gnome-terminal", "konsole", "alacritty", "kitty"];
+        for term in &terminals {
+            if Command::new(term).arg("-e").arg("bash").spawn().is_ok() {
+                return Ok(());
+            }
+        }
+        anyhow::bail!("Could not find a terminal emulator")
+    }
+}
+
+/// Open `path` with the first of `openers` that matches it, or else the
+/// system's opener
+pub fn open_file(path: &Path, openers: &[OpenerConfig]) -> Result<()> {
+    match openers.iter().find(|o| opener::matches(o, path)) {
+        Some(opener) => open_with(opener, path),
+        None => open_with_system(path),
+    }
+}
+
+/// Every opener configured for `path`, in config order
+pub fn openers_for<'a>(openers: &'a [OpenerConfig], path: &Path) -> Vec<&'a OpenerConfig> {
+    openers.iter().filter(|o| opener::matches(o, path)).collect()
+}
+
+pub fn open_with(opener: &OpenerConfig, path: &Path) -> Result<()> {
+    // Recent files don't remember a position, so editors start at the top
+    let args = opener::command_line(opener, path, 1);
+    let (program, args) = args
+        .split_first()
+        .with_context(|| format!("Opener '{}' has an empty command", opener.name))?;
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    
+    // Launch in background, detached from terminal
+    cmd.stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::null());
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0); // Create new process group
+    }
+    
+    cmd.spawn()
+        .with_context(|| format!("Failed to open {:?} with {}", path, opener.name))?;
+    Ok(())
+}
+
+/// Run `command` with the project at `dir` as its working directory
+pub fn launch_in_project(command: &ProjectCommand, dir: &Path, text_editor: &str) -> Result<()> {
+    let args = opener::project_command_line(command, dir, text_editor);
+    let (program, args) = args
+        .split_first()
+        .with_context(|| format!("Project command '{}' is empty", command.name))?;
+    let mut cmd = Command::new(program);
+    cmd.args(args).current_dir(dir);
+    
+    // Launch in background, detached from terminal
+    cmd.stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::null());
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0); // Create new process group
+    }
+    
+    cmd.spawn()
+        .with_context(|| format!("Failed to run {} in {:?}", command.name, dir))?;
+    Ok(())
+}
+
+pub fn open_with_system(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open")
+            .arg(path)
+            .spawn()
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        Ok(())
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Try xdg-open on Linux
+        Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        Ok(())
+    }
+}
+
+/// Create `path` with `contents`. An existing file is never overwritten.
+pub fn create_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        // Ensure the directory exists
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {:?}", dir))?;
+    }
+    
+    // create_new fails rather than truncating a file that's already there
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("Failed to create file {:?}", path))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Failed to write file {:?}", path))
+}
+
+/// Open a newly created file with a matching opener, or else `text_editor`
+pub fn open_new_file(path: &Path, text_editor: &str, openers: &[OpenerConfig]) -> Result<()> {
+    if let Some(opener) = openers.iter().find(|o| opener::matches(o, path)) {
+        return open_with(opener, path);
+    }
+    
+    // Open the file with the configured text editor
+    let mut cmd = Command::new(text_editor);
+    cmd.arg(path);
+    
+    // Launch in background, detached from terminal
+    cmd.stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::null());
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0); // Create new process group
+    }
+    
+    cmd.spawn()
+        .with_context(|| format!("Failed to launch text editor '{}' for file {:?}", text_editor, path))?;
+    
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::config::{OpenerConfig, ProjectCommand};
+use crate::recent_files::glob_matches_path;
+use std::path::Path;
+
+/// Terminal emulators tried for `terminal` openers when `$TERMINAL` isn't set
+const TERMINALS: &[&str] = &["x-terminal-emulator", "alacritty", "kitty", "konsole", "xterm"];
+
+pub fn matches(opener: &OpenerConfig, path: &Path) -> bool {
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+    extension.is_some_and(|ext| {
+        opener
+            .extensions
+            .iter()
+            .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+    }) || opener.globs.iter().any(|g| glob_matches_path(g, path))
+}
+
+/// Split a command line into words. Single and double quotes group words
+/// and a backslash escapes the next character.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', q) if q != Some('\'') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, Some(_)) => word.push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (c, None) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// The program and arguments that open `path` at `line` with `opener`
+pub fn command_line(opener: &OpenerConfig, path: &Path, line: usize) -> Vec<String> {
+    let path_text = path.to_string_lossy();
+    let dir = path.parent().map(|d| d.to_string_lossy()).unwrap_or_default();
+    let line = line.to_string();
+
+    let words = split_command(&opener.command);
+    let mentions_path = words.iter().any(|w| w.contains("{path}"));
+    let mut args: Vec<String> = words
+        .iter()
+        .map(|w| w.replace("{path}", &path_text).replace("{dir}", &dir).replace("{line}", &line))
+        .collect();
+    if !mentions_path {
+        args.push(path_text.into_owned());
+    }
+    if opener.terminal {
+        args.splice(0..0, [terminal(), "-e".to_string()]);
+    }
+    args
+}
+
+/// The program and arguments for running `command` in the project at
+/// `dir`. `{editor}` is a command line of its own, so it's put in before
+/// splitting; the path placeholders after, so spaces in them are kept.
+pub fn project_command_line(command: &ProjectCommand, dir: &Path, editor: &str) -> Vec<String> {
+    let dir_text = dir.to_string_lossy();
+    let name = dir.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let mut args: Vec<String> = split_command(&command.command.replace("{editor}", editor))
+        .iter()
+        .map(|w| w.replace("{dir}", &dir_text).replace("{name}", &name))
+        .collect();
+    if command.terminal {
+        // With nothing to run the terminal starts its shell
+        let prefix = if args.is_empty() { vec![terminal()] } else { vec![terminal(), "-e".to_string()] };
+        args.splice(0..0, prefix);
+    }
+    args
+}
+
+/// `$TERMINAL`, else the first known terminal emulator on the `PATH`
+fn terminal() -> String {
+    if let Some(terminal) = std::env::var("TERMINAL").ok().filter(|t| !t.is_empty()) {
+        return terminal;
+    }
+    let on_path = |name: &str| {
+        std::env::var_os("PATH")
+            .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+    };
+    TERMINALS.iter().find(|name| on_path(name)).unwrap_or(&"xterm").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opener(command: &str, terminal: bool) -> OpenerConfig {
+        OpenerConfig {
+            name: "test".to_string(),
+            extensions: vec![".RS".to_string(), "md".to_string()],
+            globs: vec!["Makefile".to_string(), "/srv/**".to_string()],
+            command: command.to_string(),
+            terminal,
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let opener = opener("nvim", false);
+        assert!(matches(&opener, Path::new("/src/main.rs")));
+        assert!(matches(&opener, Path::new("/notes/TODO.MD")));
+        assert!(matches(&opener, Path::new("/src/Makefile")));
+        assert!(matches(&opener, Path::new("/srv/site/index.html")));
+        assert!(!matches(&opener, Path::new("/src/main.py")));
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("code  --goto '{path}:{line}'"), ["code", "--goto", "{path}:{line}"]);
+        assert_eq!(split_command(r#"sh -c "cd \"{dir}\" && make" """#), ["sh", "-c", r#"cd "{dir}" && make"#, ""]);
+        assert_eq!(split_command(r"a\ b c"), ["a b", "c"]);
+    }
+
+    #[test]
+    fn test_command_line() {
+        let path = Path::new("/home/user/my notes/todo.md");
+        assert_eq!(
+            command_line(&opener("nvim +{line} {path}", false), path, 12),
+            ["nvim", "+12", "/home/user/my notes/todo.md"]
+        );
+        assert_eq!(
+            command_line(&opener("code --folder-uri {dir}", false), path, 1),
+            ["code", "--folder-uri", "/home/user/my notes", "/home/user/my notes/todo.md"]
+        );
+        let args = command_line(&opener("hx {path}", true), path, 1);
+        assert_eq!(args[1..], ["-e", "hx", "/home/user/my notes/todo.md"]);
+    }
+
+    #[test]
+    fn test_project_command_line() {
+        let dir = Path::new("/home/user/src/my app");
+        let command = |command: &str, terminal| ProjectCommand {
+            name: "test".to_string(),
+            command: command.to_string(),
+            terminal,
+            on_launch: false,
+        };
+        assert_eq!(
+            project_command_line(&command("{editor} {dir}", false), dir, "code --new-window"),
+            ["code", "--new-window", "/home/user/src/my app"]
+        );
+        let args = project_command_line(&command("tmux new -s {name}", true), dir, "vi");
+        assert_eq!(args[1..], ["-e", "tmux", "new", "-s", "my app"]);
+        assert_eq!(project_command_line(&command("", true), dir, "vi").len(), 1);
+    }
+}
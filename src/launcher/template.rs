@@ -0,0 +1,187 @@
+use crate::config::Config;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Templates shipped with velocity, as (file name, contents)
+const BUNDLED_TEMPLATES: &[(&str, &str)] = &[
+    ("note.md", include_str!("../../templates/note.md")),
+    ("journal.md", include_str!("../../templates/journal.md")),
+    ("scratch.rs", include_str!("../../templates/scratch.rs")),
+    ("scratch.py", include_str!("../../templates/scratch.py")),
+];
+
+/// Starting contents for a new file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// File name without the extension, e.g. `journal`
+    pub name: String,
+    /// Extension given to new files that don't name one
+    pub extension: String,
+    pub body: String,
+}
+
+impl Template {
+    fn from_file_name(file_name: &str, body: String) -> Self {
+        let (name, extension) = file_name.rsplit_once('.').unwrap_or((file_name, "txt"));
+        Self {
+            name: name.to_string(),
+            extension: extension.to_string(),
+            body,
+        }
+    }
+
+    /// Suggested name for a new file, unique to the second
+    pub fn default_file_name(&self, now: DateTime<Local>) -> String {
+        format!("{}_{}.{}", self.name, now.format("%Y%m%d_%H%M%S"), self.extension)
+    }
+
+    /// The body with `{{variables}}` filled in for a file at `path`
+    pub fn render(&self, path: &Path, now: DateTime<Local>) -> String {
+        substitute(&self.body, path, now)
+    }
+}
+
+/// `templates/` in the config directory
+fn templates_dir() -> Option<PathBuf> {
+    Config::config_dir().ok().map(|dir| dir.join("templates"))
+}
+
+/// The templates shipped with velocity
+pub fn bundled_templates() -> Vec<Template> {
+    BUNDLED_TEMPLATES
+        .iter()
+        .map(|(file_name, body)| Template::from_file_name(file_name, body.to_string()))
+        .collect()
+}
+
+/// The bundled templates, replaced or joined by files in `templates/`
+pub fn load_templates() -> Vec<Template> {
+    let user: Vec<Template> = templates_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let body = fs::read_to_string(entry.path()).ok()?;
+            Some(Template::from_file_name(&entry.file_name().to_string_lossy(), body))
+        })
+        .collect();
+    with_overrides(bundled_templates(), user)
+}
+
+/// `templates` with each of `user` replacing the one with the same file
+/// name, or added after them in name order
+fn with_overrides(mut templates: Vec<Template>, mut user: Vec<Template>) -> Vec<Template> {
+    user.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.extension.cmp(&b.extension)));
+    for template in user {
+        match templates
+            .iter_mut()
+            .find(|t| t.name == template.name && t.extension == template.extension)
+        {
+            Some(bundled) => *bundled = template,
+            None => templates.push(template),
+        }
+    }
+    templates
+}
+
+/// Replace `{{date}}`, `{{time}}`, `{{datetime}}`, `{{title}}`,
+/// `{{filename}}` and `{{user}}`. The date and time variables take a
+/// strftime format after a colon, as in `{{date:%A %d %B}}`. Unknown
+/// variables are left as they are.
+pub fn substitute(text: &str, path: &Path, now: DateTime<Local>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let variable = &after[..end];
+        match expand(variable.trim(), path, now) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn expand(variable: &str, path: &Path, now: DateTime<Local>) -> Option<String> {
+    let (name, format) = match variable.split_once(':') {
+        Some((name, format)) => (name, Some(format)),
+        None => (variable, None),
+    };
+    let time = |default: &str| {
+        let format = format.unwrap_or(default);
+        // An invalid format would panic when displayed
+        let valid = !StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+        valid.then(|| now.format(format).to_string())
+    };
+    match name {
+        "date" => time("%Y-%m-%d"),
+        "time" => time("%H:%M"),
+        "datetime" => time("%Y-%m-%d %H:%M"),
+        "title" => path.file_stem().map(|stem| {
+            // "my_first-note" -> "My first note"
+            let words = stem.to_string_lossy().replace(['_', '-'], " ");
+            let mut chars = words.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        }),
+        "filename" => path.file_name().map(|n| n.to_string_lossy().into_owned()),
+        "user" => std::env::var("USER").ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_substitute() {
+        let now = Local.with_ymd_and_hms(2024, 6, 21, 9, 5, 0).unwrap();
+        let path = Path::new("/notes/team_sync-notes.md");
+        assert_eq!(
+            substitute("# {{title}}\n{{date}} {{ time }} {{filename}}", path, now),
+            "# Team sync notes\n2024-06-21 09:05 team_sync-notes.md"
+        );
+        assert_eq!(substitute("{{date:%A, %d %B}}", path, now), "Friday, 21 June");
+        // Unknown variables, bad formats and stray braces are kept
+        assert_eq!(substitute("{{nope}} {{date:%Q}} {{open", path, now), "{{nope}} {{date:%Q}} {{open");
+        assert_eq!(substitute("fn main() {}", path, now), "fn main() {}");
+    }
+
+    #[test]
+    fn test_bundled_templates() {
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let journal = bundled_templates()
+            .into_iter()
+            .find(|t| t.name == "journal")
+            .unwrap();
+        assert_eq!(journal.extension, "md");
+        assert_eq!(journal.default_file_name(now), "journal_20240102_030405.md");
+        assert!(journal.render(Path::new("j.md"), now).starts_with("# Tuesday, 02 January 2024\n"));
+    }
+
+    #[test]
+    fn test_user_overrides() {
+        let user = vec![
+            Template::from_file_name("todo.md", "- [ ] ".to_string()),
+            Template::from_file_name("scratch.py", "print()\n".to_string()),
+        ];
+        let templates = with_overrides(bundled_templates(), user);
+        let names: Vec<String> = templates.iter().map(|t| format!("{}.{}", t.name, t.extension)).collect();
+        assert_eq!(names, ["note.md", "journal.md", "scratch.rs", "scratch.py", "todo.md"]);
+        // Only the template with the same extension is replaced
+        assert_eq!(templates[3].body, "print()\n");
+        assert_ne!(templates[2].body, "print()\n");
+    }
+}
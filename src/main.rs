@@ -1,8 +1,15 @@
+mod apps;
 mod ascii_globe;
+mod cli;
 mod config;
 mod launcher;
+mod notes;
+mod persist;
+mod projects;
 mod recent_files;
 mod system_stats;
+#[cfg(test)]
+mod test_util;
 mod ui;
 
 use anyhow::Result;
@@ -14,36 +21,16 @@ use crossterm::{
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io;
-use std::path::Path;
 use std::time::{Duration, Instant};
 use ui::{App, AppState};
-use ui::components::GlobeComponent;
-use ui::views::{render_home, render_apps, render_recent, render_settings};
+use ui::views::{render_help, render_home, render_apps, render_new_file, render_note_editor, render_notes, render_notice, render_open_with, render_project_menu, render_projects, render_recent, render_recent_preview, render_settings};
 
-fn find_texture_path(config_path: &str) -> String {
-    // Check if the configured path exists
-    if Path::new(config_path).exists() {
-        return config_path.to_string();
-    }
-    
-    // Try common locations relative to current directory
-    let candidates = vec![
-        config_path, // Try configured path first
-        "textures",  // Default bundled location
-    ];
-    
-    for candidate in candidates {
-        if Path::new(candidate).exists() {
-            return candidate.to_string();
-        }
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::run(&args)? {
+        return Ok(());
     }
-    
-    // Fallback to configured path even if it doesn't exist yet
-    // (user might need to create it or it will error with a helpful message)
-    config_path.to_string()
-}
 
-fn main() -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -53,15 +40,6 @@ fn main() -> Result<()> {
 
     // Initialize app
     let mut app = App::new()?;
-    // Find texture path - check multiple locations
-    let texture_path = find_texture_path(&app.config.globe.texture_path);
-    let mut globe = GlobeComponent::new(&texture_path)?;
-    
-    // Apply config to globe
-    globe.set_scale(app.config.globe.scale);
-    globe.set_speed(app.config.globe.speed);
-    globe.set_tilt(app.config.globe.tilt);
-    globe.set_lighting(app.config.globe.lighting);
     
     let target_fps = app.config.ui.target_fps;
     let frame_time = Duration::from_secs_f64(1.0 / target_fps as f64);
@@ -75,13 +53,9 @@ fn main() -> Result<()> {
         // Handle events
         if crossterm::event::poll(Duration::from_millis(0))? {
             match event::read()? {
-                Event::Resize(width, height) => {
-                    // Terminal was resized - force immediate redraw and resize stars if needed
+                Event::Resize(_, _) => {
+                    // Terminal was resized - force immediate redraw; the stars rescale themselves
                     needs_immediate_redraw = true;
-                    if let Some(ref mut stars) = app.stars {
-                        // Resize stars to full screen dimensions
-                        stars.resize(width, height);
-                    }
                 }
                 Event::Key(key) => {
                     app.handle_key(key)?;
@@ -89,60 +63,62 @@ fn main() -> Result<()> {
                         break;
                     }
                 }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    app.handle_mouse(mouse, size)?;
+                }
                 _ => {}
             }
         }
         
         // Update
         app.update();
-        globe.update()?;
+        app.globe.update()?;
         
         // Render
         let theme = app.theme();
         terminal.draw(|f| {
             match app.state {
                 AppState::Home => {
-                    render_home(f, &mut globe, &mut app.system_stats, &theme);
+                    render_home(f, &mut app.globe, &mut app.system_stats, &theme);
+                    app.menu_items.clear();
                 }
                 AppState::Apps => {
-                    // Initialize stars only if they don't exist or dimensions changed
-                    let area = f.size();
-                    if area.width > 0 && area.height > 0 {
-                        use ui::components::NightSky;
-                        let needs_init = app.stars.is_none() || 
-                            app.stars.as_ref().map(|s| s.initialized_width != area.width || s.initialized_height != area.height).unwrap_or(true);
-                        if needs_init {
-                            app.stars = Some(NightSky::new(area.width, area.height));
-                        }
-                    }
-                    render_apps(f, &mut globe, app.app_selection, &app.config, app.stars.as_mut(), &theme);
+                    app.menu_items = render_apps(f, &mut app.globe, &app.app_list, &app.config, app.hovered, &mut app.stars, &theme);
                 }
                 AppState::RecentFiles => {
-                    // Initialize stars only if they don't exist or dimensions changed
-                    let area = f.size();
-                    if area.width > 0 && area.height > 0 {
-                        use ui::components::NightSky;
-                        let needs_init = app.stars.is_none() || 
-                            app.stars.as_ref().map(|s| s.initialized_width != area.width || s.initialized_height != area.height).unwrap_or(true);
-                        if needs_init {
-                            app.stars = Some(NightSky::new(area.width, area.height));
-                        }
+                    app.menu_items = render_recent(f, &mut app.globe, &app.recent_files, app.recent_selection, app.hovered, &mut app.stars, &theme);
+                    if let Some(preview) = app.preview.as_mut() {
+                        render_recent_preview(f, &app.recent_files, app.recent_selection, preview, &theme);
                     }
-                    render_recent(f, &mut globe, &app.recent_files, app.recent_selection, app.stars.as_mut(), &theme);
                 }
                 AppState::Settings => {
-                    // Initialize stars only if they don't exist or dimensions changed
-                    let area = f.size();
-                    if area.width > 0 && area.height > 0 {
-                        use ui::components::NightSky;
-                        let needs_init = app.stars.is_none() || 
-                            app.stars.as_ref().map(|s| s.initialized_width != area.width || s.initialized_height != area.height).unwrap_or(true);
-                        if needs_init {
-                            app.stars = Some(NightSky::new(area.width, area.height));
-                        }
-                    }
-                    render_settings(f, &mut globe, &app.config, app.settings_selection, app.stars.as_mut(), &theme);
+                    app.menu_items = render_settings(f, &mut app.globe, &app.config, app.settings_selection, app.hovered, &mut app.stars, &theme);
+                }
+                AppState::Notes => {
+                    app.menu_items = render_notes(f, &mut app.globe, &app.notes, app.note_selection, app.hovered, &mut app.stars, &theme);
                 }
+                AppState::Projects => {
+                    app.menu_items = render_projects(f, &mut app.globe, &app.projects, app.project_selection, app.hovered, &mut app.stars, &theme);
+                }
+            }
+            if let Some(message) = app.notice() {
+                render_notice(f, message, &theme);
+            }
+            if let Some(editor) = app.note_editor.as_mut() {
+                render_note_editor(f, editor, &theme);
+            }
+            if let Some(prompt) = &app.new_file {
+                render_new_file(f, prompt, &theme);
+            }
+            if let Some(menu) = &app.open_with {
+                render_open_with(f, menu, &theme);
+            }
+            if let Some(menu) = &app.project_menu {
+                render_project_menu(f, menu, &app.config.projects.commands, &theme);
+            }
+            if app.show_help {
+                render_help(f, &app.keymap, app.state, &theme);
             }
         })?;
         
@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A markdown file in the notes directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub path: PathBuf,
+    /// First line of the note without its heading marks, or else the file name
+    pub title: String,
+    pub modified: SystemTime,
+}
+
+/// The `.md` files directly in `dir`, most recently changed first
+pub fn list_notes(dir: &Path) -> Vec<Note> {
+    let mut notes: Vec<Note> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("md")))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let title = first_line(&path).unwrap_or_else(|| {
+                path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+            });
+            Some(Note { path, title, modified })
+        })
+        .collect();
+    notes.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+    notes
+}
+
+/// The first non-blank line, with any `#` heading marks taken off
+fn first_line(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .take(20)
+        .map_while(|line| line.ok())
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .find(|line| !line.is_empty())
+}
+
+/// Where a note started at `now` is saved
+pub fn new_note_path(dir: &Path, now: DateTime<Local>) -> PathBuf {
+    dir.join(format!("note_{}.md", now.format("%Y%m%d_%H%M%S")))
+}
+
+/// Write `text` to the note at `path`. A new note never replaces a file
+/// already there; an existing one is replaced in a single step.
+pub fn save_note(path: &Path, text: &str, is_new: bool) -> Result<()> {
+    if !is_new {
+        return crate::persist::write_atomic(path, text.as_bytes());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("Failed to create note {:?}", path))?;
+    file.write_all(text.as_bytes())
+        .with_context(|| format!("Failed to write note {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::time::Duration;
+
+    #[test]
+    fn test_list_and_save() {
+        let dir = TempDir::new("notes");
+        let old = dir.join("old.md");
+        let new = dir.join("new.md");
+        save_note(&old, "\n## Shopping list\n- milk\n", true).unwrap();
+        save_note(&new, "", true).unwrap();
+        fs::write(dir.join("todo.txt"), "not a note").unwrap();
+        let day_ago = SystemTime::now() - Duration::from_secs(86400);
+        fs::File::options().write(true).open(&old).unwrap().set_modified(day_ago).unwrap();
+
+        let notes = list_notes(&dir);
+        let overwrite = save_note(&old, "gone", true);
+        save_note(&old, "# Updated\n", false).unwrap();
+        let updated = fs::read_to_string(&old).unwrap();
+
+        let titles: Vec<&str> = notes.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["new", "Shopping list"]);
+        assert!(overwrite.is_err());
+        assert_eq!(updated, "# Updated\n");
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Lock file guarding `path`. The data file itself can't be locked since
+/// atomic writes replace it with a new file.
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+fn open_lock(path: &Path) -> Result<File> {
+    let lock_path = lock_path(path);
+    if let Some(dir) = lock_path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open lock file {:?}", lock_path))
+}
+
+/// Run `f` holding an exclusive advisory lock on `path`, so other velocity
+/// instances wait instead of interleaving a read-modify-write
+pub fn with_lock<T>(path: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let lock = open_lock(path)?;
+    lock.lock().with_context(|| format!("Failed to lock {:?}", path))?;
+    // The lock is released when `lock` is dropped
+    f()
+}
+
+/// Replace `path` with `contents` in one step: write a temporary file in
+/// the same directory, flush it to disk and rename it over `path`. Readers
+/// see the old or the new file, never half of one.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().context("Invalid data path")?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(name);
+
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e).with_context(|| format!("Failed to write {:?}", path));
+    }
+    Ok(())
+}
+
+/// Move an unreadable `path` aside to `<name>.corrupt-<time>` so it can be
+/// inspected later. Returns the backup's path.
+pub fn back_up_corrupt(path: &Path) -> Result<PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{}", Local::now().format("%Y%m%d_%H%M%S")));
+    let backup = path.with_file_name(name);
+    fs::rename(path, &backup).with_context(|| format!("Failed to back up {:?} to {:?}", path, backup))?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_write_atomic_and_back_up() {
+        let dir = TempDir::new("persist");
+        let path = dir.join("data.json");
+        write_atomic(&path, b"first").unwrap();
+        with_lock(&path, || write_atomic(&path, b"second")).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let backup = back_up_corrupt(&path).unwrap();
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let backed_up = fs::read_to_string(&backup).unwrap();
+
+        assert_eq!(contents, "second");
+        assert_eq!(backed_up, "second");
+        // No temporary files left behind, just the backup and the lock
+        assert_eq!(left.len(), 2);
+        assert!(left[0].starts_with("data.json.corrupt-"));
+        assert_eq!(left[1], "data.json.lock");
+    }
+}
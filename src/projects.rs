@@ -0,0 +1,217 @@
+use crate::config::ProjectsConfig;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Directories never searched for projects
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "build", "dist", "__pycache__"];
+// Files looked at per project when checking for changes, so a huge
+// checkout can't stall the view
+const DIRTY_SCAN_LIMIT: usize = 2000;
+// How deep into a project the change check looks
+const DIRTY_SCAN_DEPTH: usize = 3;
+
+/// A git repository found under one of the project roots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub name: String,
+    pub path: PathBuf,
+    /// Checked out branch, or the short commit hash when detached
+    pub branch: Option<String>,
+    /// Files changed since git last wrote its index
+    pub dirty: bool,
+    /// Last git activity, in Unix seconds
+    pub active_at: u64,
+}
+
+/// The repositories under the configured roots
+pub fn find(config: &ProjectsConfig) -> Vec<Project> {
+    let roots: Vec<PathBuf> = config
+        .roots
+        .iter()
+        .map(|root| match (root.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(root),
+        })
+        .collect();
+    scan(&roots, config.max_depth)
+}
+
+/// Every git repository within `max_depth` directories of `roots`, most
+/// recently active first. Repositories aren't searched for nested ones.
+pub fn scan(roots: &[PathBuf], max_depth: usize) -> Vec<Project> {
+    let mut projects = Vec::new();
+    for root in roots {
+        find_projects(root, max_depth, &mut projects);
+    }
+    projects.sort_by(|a, b| b.active_at.cmp(&a.active_at).then_with(|| a.name.cmp(&b.name)));
+    projects.dedup_by(|a, b| a.path == b.path);
+    projects
+}
+
+fn find_projects(dir: &Path, depth: usize, projects: &mut Vec<Project>) {
+    if let Some(project) = read_project(dir) {
+        projects.push(project);
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // file_type doesn't follow symlinks, so linked trees aren't walked twice
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir && !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_ref()) {
+            find_projects(&entry.path(), depth - 1, projects);
+        }
+    }
+}
+
+/// The project at `dir`, if it's a git work tree
+pub fn read_project(dir: &Path) -> Option<Project> {
+    let git_dir = git_dir(dir)?;
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok();
+    let index_time = modified(&git_dir.join("index"));
+    let active_at = [
+        index_time,
+        modified(&git_dir.join("HEAD")),
+        modified(&git_dir.join("logs").join("HEAD")),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .map(unix_seconds)
+    .unwrap_or(0);
+
+    Some(Project {
+        name: dir.file_name()?.to_string_lossy().into_owned(),
+        path: dir.to_path_buf(),
+        branch: head.as_deref().and_then(parse_head),
+        dirty: index_time.is_some_and(|index| changed_since(dir, index, DIRTY_SCAN_DEPTH, &mut 0)),
+        active_at,
+    })
+}
+
+/// `.git` in `dir`, or where a `.git` file points for worktrees and
+/// submodules ("gitdir: <path>")
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let pointer = fs::read_to_string(&dot_git).ok()?;
+    let target = pointer.strip_prefix("gitdir:")?.trim();
+    Some(dir.join(target))
+}
+
+/// Branch name from the contents of `.git/HEAD`
+fn parse_head(head: &str) -> Option<String> {
+    let head = head.trim();
+    match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            Some(reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string())
+        }
+        // A detached HEAD holds the commit hash
+        None => head.get(..7).map(str::to_string),
+    }
+}
+
+/// Whether any file under `dir` changed after `since`. Staging or
+/// committing rewrites the index, so a newer file is very likely an
+/// uncommitted change.
+fn changed_since(dir: &Path, since: SystemTime, depth: usize, seen: &mut usize) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        *seen += 1;
+        if *seen > DIRTY_SCAN_LIMIT {
+            return false;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_file() {
+            if modified(&entry.path()).is_some_and(|time| time > since) {
+                return true;
+            }
+        } else if file_type.is_dir()
+            && depth > 0
+            && name != ".git"
+            && !SKIP_DIRS.contains(&name.as_ref())
+            && changed_since(&entry.path(), since, depth - 1, seen)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_head() {
+        assert_eq!(parse_head("ref: refs/heads/feature/globe\n"), Some("feature/globe".to_string()));
+        assert_eq!(parse_head("4f031cbd2e8a41c0b7f1e3a2c9d8e7f6a5b4c3d2\n"), Some("4f031cb".to_string()));
+        assert_eq!(parse_head(""), None);
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = TempDir::new("projects");
+        let old = root.join("work/old");
+        let new = root.join("new");
+        let worktree = root.join("worktree");
+        for git in [old.join(".git"), new.join(".git"), new.join("nested/.git"), root.join("wt-git")] {
+            fs::create_dir_all(git).unwrap();
+        }
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(old.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(new.join(".git/HEAD"), "ref: refs/heads/dev\n").unwrap();
+        fs::write(root.join("wt-git/HEAD"), "ref: refs/heads/wt\n").unwrap();
+        fs::write(worktree.join(".git"), format!("gitdir: {}\n", root.join("wt-git").display())).unwrap();
+        fs::write(old.join(".git/index"), "").unwrap();
+        fs::write(new.join(".git/index"), "").unwrap();
+        fs::write(new.join("main.rs"), "").unwrap();
+
+        // An edit after the index was written makes "new" dirty and recent;
+        // "old" was last touched an hour ago
+        let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        let index = fs::File::options().write(true).open(new.join(".git/index")).unwrap();
+        index.set_modified(hour_ago).unwrap();
+        for file in [".git/HEAD", ".git/index"] {
+            fs::File::options().write(true).open(old.join(file)).unwrap().set_modified(hour_ago).unwrap();
+        }
+
+        let projects = scan(&[root.to_path_buf()], 3);
+
+        let summary: Vec<(&str, Option<&str>, bool)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.branch.as_deref(), p.dirty))
+            .collect();
+        // The nested repository and the worktree's git dir aren't listed
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0], ("new", Some("dev"), true));
+        assert!(summary.contains(&("worktree", Some("wt"), false)));
+        assert_eq!(summary[2], ("old", Some("main"), false));
+    }
+}
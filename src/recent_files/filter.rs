@@ -0,0 +1,215 @@
+use crate::config::FilterConfig;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// Bytes read from a file to sniff its type
+const SNIFF_BYTES: usize = 512;
+
+/// Magic numbers of common binary formats
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x7fELF", "application/x-executable"),
+    (b"#!", "text/x-script"),
+];
+
+/// Decides which files are tracked in the recent files list. Exclude rules
+/// win over include rules; files without an extension can be let in by
+/// their sniffed MIME type.
+pub struct FileFilter {
+    extensions: Vec<String>,
+    exclude_extensions: Vec<String>,
+    globs: Vec<String>,
+    exclude_globs: Vec<String>,
+    mime_types: Vec<String>,
+    sniff_mime: bool,
+}
+
+impl FileFilter {
+    pub fn new(config: &FilterConfig) -> Self {
+        let lowercase = |list: &[String]| list.iter().map(|e| e.trim_start_matches('.').to_lowercase()).collect();
+        let home = dirs::home_dir();
+        // "~/notes/*" is more useful than spelling out the home directory
+        let expand = |list: &[String]| {
+            list.iter()
+                .map(|glob| match (glob.strip_prefix("~/"), &home) {
+                    (Some(rest), Some(home)) => format!("{}/{}", home.display(), rest),
+                    _ => glob.clone(),
+                })
+                .collect()
+        };
+        Self {
+            extensions: lowercase(&config.extensions),
+            exclude_extensions: lowercase(&config.exclude_extensions),
+            globs: expand(&config.globs),
+            exclude_globs: expand(&config.exclude_globs),
+            mime_types: config.mime_types.clone(),
+            sniff_mime: config.sniff_mime,
+        }
+    }
+
+    pub fn accepts(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+        if extension.as_ref().is_some_and(|e| self.exclude_extensions.contains(e))
+            || self.exclude_globs.iter().any(|g| glob_matches_path(g, path))
+        {
+            return false;
+        }
+        if extension.as_ref().is_some_and(|e| self.extensions.contains(e))
+            || self.globs.iter().any(|g| glob_matches_path(g, path))
+        {
+            return true;
+        }
+        // Only files like Makefile or scripts, whose names say nothing
+        if extension.is_some() || !self.sniff_mime {
+            return false;
+        }
+        read_head(path).is_some_and(|head| {
+            let mime = sniff_mime(&head);
+            self.mime_types.iter().any(|pattern| mime_matches(pattern, mime))
+        })
+    }
+}
+
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    File::open(path).ok()?.take(SNIFF_BYTES as u64).read_to_end(&mut head).ok()?;
+    Some(head)
+}
+
+/// MIME type of a file from its first bytes: a known magic number, else
+/// text if it decodes as UTF-8 without NULs
+pub fn sniff_mime(head: &[u8]) -> &'static str {
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return mime;
+    }
+    // The sample may end partway through a multi-byte character
+    let text = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    if text && !head.contains(&0) {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// Whether `mime` matches `pattern`, which may end in `/*` as in `text/*`
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(kind) => mime.split('/').next() == Some(kind),
+        None => pattern == mime,
+    }
+}
+
+/// Patterns with a `/` match the whole path, others just the file name
+pub fn glob_matches_path(glob: &str, path: &Path) -> bool {
+    if glob.contains('/') {
+        path.to_str().is_some_and(|p| glob_matches(glob, p))
+    } else {
+        path.file_name().and_then(|n| n.to_str()).is_some_and(|n| glob_matches(glob, n))
+    }
+}
+
+/// Shell-style matching: `?` is one character, `*` any run without `/`,
+/// `**` any run at all
+fn glob_matches(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches_from(&glob, &text)
+}
+
+fn matches_from(glob: &[char], text: &[char]) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| matches_from(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let run = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=run).any(|i| matches_from(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => matches!(text, [c, tail @ ..] if *c != '/' && matches_from(rest, tail)),
+        [g, rest @ ..] => matches!(text, [c, tail @ ..] if c == g && matches_from(rest, tail)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.rs", "main.rs"));
+        assert!(!glob_matches("*.rs", "main.rsx"));
+        assert!(glob_matches("Makefile*", "Makefile.am"));
+        assert!(glob_matches("file?.txt", "file1.txt"));
+        assert!(!glob_matches("/tmp/*.txt", "/tmp/a/b.txt"));
+        assert!(glob_matches("/tmp/**.txt", "/tmp/a/b.txt"));
+        assert!(glob_matches("**/.git/**", "/home/user/src/.git/COMMIT_EDITMSG"));
+    }
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\n\0\0"), "image/png");
+        assert_eq!(sniff_mime(b"#!/bin/sh\necho hi\n"), "text/x-script");
+        assert_eq!(sniff_mime(b"all:\n\tcargo build\n"), "text/plain");
+        // Cut off inside a multi-byte character is still text
+        assert_eq!(sniff_mime(&"caf\u{e9}".as_bytes()[..4]), "text/plain");
+        assert_eq!(sniff_mime(b"\x00\x01\x02"), "application/octet-stream");
+        assert!(mime_matches("text/*", "text/x-script"));
+        assert!(!mime_matches("text/*", "application/pdf"));
+        assert!(mime_matches("application/pdf", "application/pdf"));
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let filter = FileFilter::new(&FilterConfig::default());
+        assert!(filter.accepts(Path::new("/src/main.RS")));
+        assert!(filter.accepts(Path::new("/src/Dockerfile")));
+        assert!(!filter.accepts(Path::new("/photos/cat.png")));
+        // Git's scratch files are excluded even though they're text
+        assert!(!filter.accepts(Path::new("/src/.git/COMMIT_EDITMSG.md")));
+    }
+
+    #[test]
+    fn test_configured_rules() {
+        let config = FilterConfig {
+            extensions: vec![".pdf".to_string(), "txt".to_string()],
+            exclude_extensions: vec!["log".to_string()],
+            globs: vec!["*.log".to_string()],
+            exclude_globs: vec!["/scratch/**".to_string()],
+            mime_types: vec!["text/*".to_string()],
+            sniff_mime: true,
+        };
+        let filter = FileFilter::new(&config);
+        assert!(filter.accepts(Path::new("/docs/paper.pdf")));
+        assert!(!filter.accepts(Path::new("/scratch/notes.txt")));
+        // Exclusions beat inclusions
+        assert!(!filter.accepts(Path::new("/var/app.log")));
+
+        let dir = TempDir::new("filter");
+        let script = dir.join("build");
+        let binary = dir.join("tool");
+        let unknown = dir.join("data.bin");
+        fs::write(&script, "#!/bin/sh\nmake\n").unwrap();
+        fs::write(&binary, b"\x7fELF\x02\x01\x01\0").unwrap();
+        fs::write(&unknown, "plain text").unwrap();
+        let accepted: Vec<bool> = [&script, &binary, &unknown, &PathBuf::from("/missing")]
+            .iter()
+            .map(|p| filter.accepts(p))
+            .collect();
+
+        // Only extensionless files are sniffed
+        assert_eq!(accepted, [true, false, false, false]);
+    }
+}
@@ -0,0 +1,280 @@
+use chrono::DateTime;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Editors whose command lines in shell history name the files they open
+const EDITORS: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "emacs", "code", "hx", "micro", "kak", "gedit", "subl",
+];
+
+/// A file some other program opened, with a Unix timestamp in seconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedFile {
+    pub path: PathBuf,
+    pub opened_at: u64,
+}
+
+/// `~/.local/share/recently-used.xbel`, where GTK and other XDG desktop
+/// apps record the files they open
+pub fn xbel_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("recently-used.xbel"))
+}
+
+/// Shell history files that exist: bash's and zsh's
+pub fn shell_history_paths() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    [".bash_history", ".zsh_history"]
+        .iter()
+        .map(|name| home.join(name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Vim's MRU list
+pub fn viminfo_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".viminfo"))
+}
+
+/// Local files from an XBEL bookmark file, each at the latest of its
+/// added, modified and visited times
+pub fn parse_xbel(content: &str) -> Vec<ImportedFile> {
+    let mut files = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<bookmark ") {
+        rest = &rest[start + "<bookmark ".len()..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        let Some(path) = attribute(tag, "href").and_then(|href| file_uri_to_path(&href)) else {
+            continue;
+        };
+        let opened_at = ["added", "modified", "visited"]
+            .iter()
+            .filter_map(|name| attribute(tag, name))
+            .filter_map(|time| DateTime::parse_from_rfc3339(&time).ok())
+            .map(|time| time.timestamp().max(0) as u64)
+            .max()
+            .unwrap_or(0);
+        files.push(ImportedFile { path, opened_at });
+    }
+    files
+}
+
+/// Value of attribute `name` in the inside of an XML start tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut search = tag;
+    loop {
+        let index = search.find(name)?;
+        let before = search[..index].chars().last();
+        let after = &search[index + name.len()..];
+        // Whole attribute names only, so "modified" doesn't match "added"
+        if before.is_none_or(char::is_whitespace) {
+            if let Some(value) = after.trim_start().strip_prefix('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next().filter(|&q| q == '"' || q == '\'')?;
+                let value = &value[1..];
+                let end = value.find(quote)?;
+                return Some(unescape_xml(&value[..end]));
+            }
+        }
+        search = after;
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Path of a `file://` URI, percent-decoded. Other schemes give `None`.
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // Skip an optional host, e.g. file://localhost/home/...
+    let path = &rest[rest.find('/')?..];
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Files named on editor command lines in bash or zsh history. Only
+/// absolute and `~` paths count since the working directory is unknown.
+/// Lines without a timestamp get `default_time`.
+pub fn parse_shell_history(content: &str, home: &Path, default_time: u64) -> Vec<ImportedFile> {
+    let mut files = Vec::new();
+    // bash writes "#<time>" lines before commands when HISTTIMEFORMAT is set
+    let mut bash_time = None;
+    for line in content.lines() {
+        if let Some(time) = line.strip_prefix('#').and_then(|t| t.trim().parse().ok()) {
+            bash_time = Some(time);
+            continue;
+        }
+        // zsh extended history: ": <time>:<duration>;<command>"
+        let (time, command) = match line.strip_prefix(": ").and_then(|l| l.split_once(';')) {
+            Some((meta, command)) => (meta.split(':').next().and_then(|t| t.trim().parse().ok()), command),
+            None => (bash_time.take(), line),
+        };
+
+        let mut words = command.split_whitespace();
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let program = program.rsplit('/').next().unwrap_or(program);
+        if !EDITORS.contains(&program) {
+            continue;
+        }
+        for word in words.filter(|w| !w.starts_with('-')) {
+            let word = word.trim_matches(|c| c == '"' || c == '\'');
+            let path = if let Some(rest) = word.strip_prefix("~/") {
+                home.join(rest)
+            } else if word.starts_with('/') {
+                PathBuf::from(word)
+            } else {
+                continue;
+            };
+            files.push(ImportedFile {
+                path,
+                opened_at: time.unwrap_or(default_time),
+            });
+        }
+    }
+    files
+}
+
+/// Files from the "> path" entries of a viminfo file, with the time of
+/// the entry's first mark line ("\t*\t<time>\t0")
+pub fn parse_viminfo(content: &str, home: &Path) -> Vec<ImportedFile> {
+    let mut files: Vec<ImportedFile> = Vec::new();
+    let mut current: Option<ImportedFile> = None;
+    for line in content.lines() {
+        if let Some(path) = line.strip_prefix("> ") {
+            files.extend(current.take());
+            let path = match path.strip_prefix("~/") {
+                Some(rest) => home.join(rest),
+                None => PathBuf::from(path),
+            };
+            current = Some(ImportedFile { path, opened_at: 0 });
+        } else if let Some(file) = current.as_mut() {
+            let mut fields = line.trim_start().split('\t');
+            if fields.next() == Some("*") && file.opened_at == 0 {
+                file.opened_at = fields.next().and_then(|t| t.trim().parse().ok()).unwrap_or(0);
+            }
+        }
+    }
+    files.extend(current);
+    files
+}
+
+/// Read `path` and parse it with `parse`, or nothing if it can't be read
+pub fn read_with(path: &Path, parse: impl Fn(&str, u64) -> Vec<ImportedFile>) -> Vec<ImportedFile> {
+    let Ok(content) = fs::read(path) else {
+        return Vec::new();
+    };
+    // History files aren't always valid UTF-8
+    let content = String::from_utf8_lossy(&content);
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    parse(&content, modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XBEL: &str = include_str!("../../tests/fixtures/recently-used.xbel");
+
+    #[test]
+    fn test_parse_xbel() {
+        let files = parse_xbel(XBEL);
+        assert_eq!(
+            files,
+            vec![
+                ImportedFile {
+                    path: PathBuf::from("/home/user/notes/todo list.md"),
+                    // visited is the latest of the three
+                    opened_at: 1_704_110_400,
+                },
+                ImportedFile {
+                    path: PathBuf::from("/home/user/src/main.rs"),
+                    opened_at: 1_704_067_200,
+                },
+                ImportedFile {
+                    path: PathBuf::from("/home/user/R&D/report.txt"),
+                    opened_at: 1_704_067_201,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_uri_to_path() {
+        assert_eq!(file_uri_to_path("file:///tmp/a%20b.txt"), Some(PathBuf::from("/tmp/a b.txt")));
+        assert_eq!(file_uri_to_path("file://localhost/tmp/x"), Some(PathBuf::from("/tmp/x")));
+        assert_eq!(file_uri_to_path("file:///tmp/100%"), Some(PathBuf::from("/tmp/100%")));
+        assert_eq!(file_uri_to_path("https://example.com/a.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_shell_history() {
+        let home = Path::new("/home/user");
+        let zsh = ": 1700000000:0;nvim ~/notes.md\n: 1700000100:0;ls /tmp\n: 1700000200:0;vim -p /etc/hosts rel.txt\n";
+        assert_eq!(
+            parse_shell_history(zsh, home, 5),
+            vec![
+                ImportedFile { path: PathBuf::from("/home/user/notes.md"), opened_at: 1_700_000_000 },
+                ImportedFile { path: PathBuf::from("/etc/hosts"), opened_at: 1_700_000_200 },
+            ]
+        );
+
+        let bash = "#1700000300\n/usr/bin/nano /tmp/a.txt\nnano /tmp/b.txt\n";
+        assert_eq!(
+            parse_shell_history(bash, home, 5),
+            vec![
+                ImportedFile { path: PathBuf::from("/tmp/a.txt"), opened_at: 1_700_000_300 },
+                ImportedFile { path: PathBuf::from("/tmp/b.txt"), opened_at: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_viminfo() {
+        let viminfo = "# File marks:\n'0  1  0  ~/a.txt\n\n# History of marks within files (newest to oldest):\n\n> ~/a.txt\n\t*\t1700000500\t0\n\t\"\t1\t0\n\n> /etc/fstab\n\t\"\t3\t0\n";
+        assert_eq!(
+            parse_viminfo(viminfo, Path::new("/home/user")),
+            vec![
+                ImportedFile { path: PathBuf::from("/home/user/a.txt"), opened_at: 1_700_000_500 },
+                ImportedFile { path: PathBuf::from("/etc/fstab"), opened_at: 0 },
+            ]
+        );
+    }
+}
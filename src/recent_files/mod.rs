@@ -0,0 +1,474 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::config::{Config, RecentConfig, RecentRanking};
+use crate::persist;
+use filter::FileFilter;
+use import::ImportedFile;
+
+mod filter;
+mod import;
+
+pub use filter::{glob_matches_path, sniff_mime};
+
+// Files from before open counts were kept were opened at least once
+fn default_open_count() -> u32 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RecentFileEntry {
+    pub path: PathBuf,
+    pub opened_at: u64,
+    #[serde(default = "default_open_count")]
+    pub open_count: u32,
+    /// Pinned files are listed first and never dropped for the cap
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl RecentFileEntry {
+    fn new(path: PathBuf, opened_at: u64) -> Self {
+        Self {
+            path,
+            opened_at,
+            open_count: 1,
+            pinned: false,
+            tags: Vec::new(),
+        }
+    }
+
+    /// zoxide-style frecency: the open count, weighted down as the last
+    /// open gets older
+    fn frecency(&self, now: u64) -> f64 {
+        const HOUR: u64 = 60 * 60;
+        let age = now.saturating_sub(self.opened_at);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < 24 * HOUR {
+            2.0
+        } else if age < 7 * 24 * HOUR {
+            0.5
+        } else {
+            0.25
+        };
+        self.open_count as f64 * weight
+    }
+}
+
+/// A file taken off the list, so importing doesn't bring it straight back
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+struct RemovedFile {
+    path: PathBuf,
+    removed_at: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+struct RecentFilesData {
+    files: Vec<RecentFileEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    removed: Vec<RemovedFile>,
+}
+
+// Modified time and size of the data file, to tell when it has changed
+type Stamp = (Option<SystemTime>, u64);
+
+pub struct RecentFiles {
+    config: RecentConfig,
+    filter: FileFilter,
+    data_path: PathBuf,
+    // The file as last read or written, so unchanged files aren't parsed
+    // again on every frame
+    cache: RefCell<Option<(Stamp, RecentFilesData)>>,
+    // Problem reading the file that the user should hear about
+    warning: RefCell<Option<String>>,
+}
+
+impl RecentFiles {
+    pub fn new(config: &RecentConfig) -> Result<Self> {
+        let config_dir = Config::config_dir()?;
+        let data_path = config_dir.join("recent_files.json");
+        Ok(Self::with_path(config, data_path))
+    }
+
+    fn with_path(config: &RecentConfig, data_path: PathBuf) -> Self {
+        Self {
+            config: config.clone(),
+            filter: FileFilter::new(&config.filter),
+            data_path,
+            cache: RefCell::new(None),
+            warning: RefCell::new(None),
+        }
+    }
+
+    /// The last problem reading the list, once
+    pub fn take_warning(&self) -> Option<String> {
+        self.warning.borrow_mut().take()
+    }
+
+    fn stamp(&self) -> Option<Stamp> {
+        let metadata = fs::metadata(&self.data_path).ok()?;
+        Some((metadata.modified().ok(), metadata.len()))
+    }
+
+    fn cached(&self, stamp: Option<Stamp>) -> Option<RecentFilesData> {
+        let cache = self.cache.borrow();
+        let (cached_stamp, data) = cache.as_ref()?;
+        (Some(*cached_stamp) == stamp).then(|| data.clone())
+    }
+
+    fn load_data(&self) -> Result<RecentFilesData> {
+        if let Some(data) = self.cached(self.stamp()) {
+            return Ok(data);
+        }
+        persist::with_lock(&self.data_path, || self.read_data())
+    }
+
+    /// Read the file, which the caller has locked. A file that doesn't
+    /// parse is moved aside and the list starts empty.
+    fn read_data(&self) -> Result<RecentFilesData> {
+        let stamp = self.stamp();
+        if let Some(data) = self.cached(stamp) {
+            return Ok(data);
+        }
+        let content = match fs::read_to_string(&self.data_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(RecentFilesData::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read recent files from {:?}", self.data_path))
+            }
+        };
+
+        match serde_json::from_str::<RecentFilesData>(&content) {
+            Ok(data) => {
+                if let Some(stamp) = stamp {
+                    *self.cache.borrow_mut() = Some((stamp, data.clone()));
+                }
+                Ok(data)
+            }
+            Err(e) => {
+                let warning = match persist::back_up_corrupt(&self.data_path) {
+                    Ok(backup) => format!("Recent files list was corrupt ({}); moved it to {:?} and started fresh", e, backup),
+                    Err(backup_error) => format!("Recent files list was corrupt ({}): {:#}", e, backup_error),
+                };
+                *self.warning.borrow_mut() = Some(warning);
+                Ok(RecentFilesData::default())
+            }
+        }
+    }
+
+    /// Change the list with `change` while holding the lock, so another
+    /// velocity saving at the same time can't lose the update
+    fn update<T>(&self, change: impl FnOnce(&mut RecentFilesData) -> T) -> Result<T> {
+        persist::with_lock(&self.data_path, || {
+            let mut data = self.read_data()?;
+            let result = change(&mut data);
+
+            let content = serde_json::to_string_pretty(&data)
+                .context("Failed to serialize recent files")?;
+            persist::write_atomic(&self.data_path, content.as_bytes())?;
+            if let Some(stamp) = self.stamp() {
+                *self.cache.borrow_mut() = Some((stamp, data));
+            }
+            Ok(result)
+        })
+    }
+
+    pub fn add_file(&self, path: PathBuf) -> Result<()> {
+        if !self.filter.accepts(&path) {
+            return Ok(()); // Silently skip filtered out files
+        }
+
+        self.update(|data| {
+            let now = now();
+            // Opening a removed file here puts it back
+            data.removed.retain(|r| r.path != path);
+            let files = &mut data.files;
+            match files.iter_mut().find(|e| e.path == path) {
+                Some(entry) => {
+                    entry.opened_at = now;
+                    entry.open_count = entry.open_count.saturating_add(1);
+                }
+                None => files.push(RecentFileEntry::new(path, now)),
+            }
+            rank(files, self.config.ranking, now);
+            cap(files, self.config.max_files);
+        })
+    }
+
+    /// Pin or unpin `path`. Returns whether it is now pinned.
+    pub fn toggle_pin(&self, path: &Path) -> Result<bool> {
+        self.update(|data| {
+            let files = &mut data.files;
+            let Some(entry) = files.iter_mut().find(|e| e.path == path) else {
+                return false;
+            };
+            entry.pinned = !entry.pinned;
+            let pinned = entry.pinned;
+            rank(files, self.config.ranking, now());
+            cap(files, self.config.max_files);
+            pinned
+        })
+    }
+
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        self.update(|data| forget(data, |e| e.path == path, now()))
+    }
+
+    /// Forget every file that isn't pinned
+    pub fn clear(&self) -> Result<()> {
+        self.update(|data| forget(data, |e| !e.pinned, now()))
+    }
+
+    /// Merge in files opened by other programs, as configured. Returns how
+    /// many of the listed files are new.
+    pub fn import(&self) -> Result<usize> {
+        let config = &self.config;
+        let home = dirs::home_dir().unwrap_or_default();
+        let mut imported = Vec::new();
+        if config.import_xbel {
+            if let Some(path) = import::xbel_path() {
+                imported.extend(import::read_with(&path, |content, _| import::parse_xbel(content)));
+            }
+        }
+        if config.import_shell_history {
+            for path in import::shell_history_paths() {
+                imported.extend(import::read_with(&path, |content, modified| {
+                    import::parse_shell_history(content, &home, modified)
+                }));
+            }
+        }
+        if config.import_editor_history {
+            if let Some(path) = import::viminfo_path() {
+                imported.extend(import::read_with(&path, |content, _| import::parse_viminfo(content, &home)));
+            }
+        }
+
+        self.merge_imported(imported)
+    }
+
+    fn merge_imported(&self, imported: Vec<ImportedFile>) -> Result<usize> {
+        self.update(|data| {
+            let added = merge(data, imported, &self.filter);
+            rank(&mut data.files, self.config.ranking, now());
+            cap(&mut data.files, self.config.max_files);
+            added
+        })
+    }
+
+    /// Every file in list order: pinned first, then by the configured ranking
+    pub fn entries(&self) -> Result<Vec<RecentFileEntry>> {
+        let mut files = self.load_data()?.files;
+        // Frecency decays with time, so rank again rather than trusting the saved order
+        rank(&mut files, self.config.ranking, now());
+        Ok(files)
+    }
+
+    pub fn get_files(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.entries()?.into_iter().map(|e| e.path).collect())
+    }
+
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Sort `files` into list order: pinned files first, then by `ranking`,
+/// with the most recently opened first among equals
+fn rank(files: &mut [RecentFileEntry], ranking: RecentRanking, now: u64) {
+    files.sort_by(|a, b| {
+        b.pinned.cmp(&a.pinned).then_with(|| match ranking {
+            RecentRanking::Frecency => b.frecency(now).total_cmp(&a.frecency(now)),
+            RecentRanking::Recency => std::cmp::Ordering::Equal,
+        })
+        .then_with(|| b.opened_at.cmp(&a.opened_at))
+    });
+}
+
+/// Drop the lowest ranked unpinned files beyond `max_files`
+fn cap(files: &mut Vec<RecentFileEntry>, max_files: usize) {
+    let mut kept = 0;
+    files.retain(|e| {
+        if e.pinned {
+            return true;
+        }
+        kept += 1;
+        kept <= max_files
+    });
+}
+
+/// Drop the files matching `remove` from the list, remembering them as
+/// removed at `now`
+fn forget(data: &mut RecentFilesData, remove: impl Fn(&RecentFileEntry) -> bool, now: u64) {
+    let (removed, kept) = std::mem::take(&mut data.files).into_iter().partition(remove);
+    data.files = kept;
+    for entry in removed {
+        data.removed.retain(|r| r.path != entry.path);
+        data.removed.push(RemovedFile { path: entry.path, removed_at: now });
+    }
+}
+
+/// Merge the `imported` files `filter` accepts into the list by path,
+/// keeping each path's latest time, and drop unpinned files that no longer
+/// exist. Files removed since they were last opened stay off the list.
+/// Returns how many of the remaining paths weren't listed before.
+fn merge(data: &mut RecentFilesData, imported: Vec<ImportedFile>, filter: &FileFilter) -> usize {
+    let files = &mut data.files;
+    let known: Vec<PathBuf> = files.iter().map(|e| e.path.clone()).collect();
+    for file in imported {
+        if !filter.accepts(&file.path) {
+            continue;
+        }
+        let removed = data.removed.iter().any(|r| r.path == file.path && r.removed_at >= file.opened_at);
+        match files.iter_mut().find(|e| e.path == file.path) {
+            Some(entry) => entry.opened_at = entry.opened_at.max(file.opened_at),
+            None if removed => {}
+            None => files.push(RecentFileEntry::new(file.path, file.opened_at)),
+        }
+    }
+    files.retain(|e| e.pinned || e.path.exists());
+    data.removed.retain(|r| r.path.exists());
+    files.iter().filter(|e| !known.contains(&e.path)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::config::FilterConfig;
+
+    fn entry(path: &Path, opened_at: u64) -> RecentFileEntry {
+        RecentFileEntry::new(path.to_path_buf(), opened_at)
+    }
+
+    fn paths(files: &[RecentFileEntry]) -> Vec<&str> {
+        files.iter().map(|e| e.path.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_merge_imported_files() {
+        let dir = TempDir::new("recent");
+        let notes = dir.join("notes.md");
+        let main = dir.join("main.rs");
+        let image = dir.join("photo.png");
+        for path in [&notes, &main, &image] {
+            fs::write(path, "").unwrap();
+        }
+
+        let mut data = RecentFilesData {
+            files: vec![entry(&notes, 100), entry(&dir.join("deleted.txt"), 90)],
+            removed: Vec::new(),
+        };
+        let imported = vec![
+            ImportedFile { path: main.clone(), opened_at: 150 },
+            ImportedFile { path: notes.clone(), opened_at: 50 },
+            ImportedFile { path: notes.clone(), opened_at: 200 },
+            ImportedFile { path: image.clone(), opened_at: 300 },
+            ImportedFile { path: dir.join("gone.rs"), opened_at: 400 },
+        ];
+        let added = merge(&mut data, imported, &FileFilter::new(&FilterConfig::default()));
+        let mut files = data.files;
+        rank(&mut files, RecentRanking::Recency, 1000);
+
+        assert_eq!(added, 1);
+        let paths: Vec<_> = files.iter().map(|e| (e.path.clone(), e.opened_at)).collect();
+        assert_eq!(paths, vec![(notes, 200), (main, 150)]);
+    }
+
+    #[test]
+    fn test_removed_files_stay_removed() {
+        let dir = TempDir::new("removed");
+        let notes = dir.join("notes.md");
+        let todo = dir.join("todo.md");
+        for path in [&notes, &todo] {
+            fs::write(path, "").unwrap();
+        }
+        let recent = RecentFiles::with_path(&RecentConfig::default(), dir.join("recent_files.json"));
+        let imported = |opened_at| {
+            vec![
+                ImportedFile { path: notes.clone(), opened_at },
+                ImportedFile { path: todo.clone(), opened_at },
+            ]
+        };
+
+        recent.merge_imported(imported(100)).unwrap();
+        recent.remove(&notes).unwrap();
+        recent.merge_imported(imported(100)).unwrap();
+        let after_remove = recent.get_files().unwrap();
+        recent.clear().unwrap();
+        recent.merge_imported(imported(100)).unwrap();
+        let after_clear = recent.get_files().unwrap();
+        // Opening a file again elsewhere brings it back
+        recent.merge_imported(imported(now() + 60)).unwrap();
+        let reopened = recent.get_files().unwrap();
+
+        assert_eq!(after_remove, vec![todo.clone()]);
+        assert!(after_clear.is_empty());
+        assert_eq!(reopened.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_old_format() {
+        let json = r#"{"files": [{"path": "/tmp/a.txt", "opened_at": 100}]}"#;
+        let data: RecentFilesData = serde_json::from_str(json).unwrap();
+        assert_eq!(data.files, vec![entry(Path::new("/tmp/a.txt"), 100)]);
+    }
+
+    #[test]
+    fn test_rank_and_cap() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = 30 * DAY;
+        let mut often = entry(Path::new("/often"), now - 2 * DAY);
+        often.open_count = 10;
+        let recent = entry(Path::new("/recent"), now - 60);
+        let mut pinned = entry(Path::new("/pinned"), 0);
+        pinned.pinned = true;
+        let old = entry(Path::new("/old"), now - 20 * DAY);
+        let mut files = vec![old, recent, pinned, often];
+
+        rank(&mut files, RecentRanking::Frecency, now);
+        assert_eq!(paths(&files), ["/pinned", "/often", "/recent", "/old"]);
+        rank(&mut files, RecentRanking::Recency, now);
+        assert_eq!(paths(&files), ["/pinned", "/recent", "/often", "/old"]);
+
+        // Pinned files don't count towards the cap
+        cap(&mut files, 2);
+        assert_eq!(paths(&files), ["/pinned", "/recent", "/often"]);
+    }
+
+    #[test]
+    fn test_recover_corrupt_file() {
+        let dir = TempDir::new("corrupt");
+        let data_path = dir.join("recent_files.json");
+        fs::write(&data_path, "{\"files\": [").unwrap();
+        let notes = dir.join("notes.md");
+
+        let recent = RecentFiles::with_path(&RecentConfig::default(), data_path.clone());
+        let before = recent.get_files().unwrap();
+        let warning = recent.take_warning();
+        recent.add_file(notes.clone()).unwrap();
+        let after = recent.get_files().unwrap();
+        let saved = fs::read_to_string(&data_path).unwrap();
+        let backups = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+
+        assert!(before.is_empty());
+        assert!(warning.unwrap().contains("corrupt"));
+        assert_eq!(recent.take_warning(), None);
+        assert_eq!(after, vec![notes]);
+        assert!(saved.contains("notes.md"));
+        assert_eq!(backups, 1);
+    }
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the unit tests
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A scratch directory in the system temp directory, removed along with
+/// everything in it when dropped, so a failing test doesn't leave it behind
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// A new empty directory, unique to `name` and this test run
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("velocity-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
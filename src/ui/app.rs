@@ -1,16 +1,53 @@
-use crate::config::Config;
+use crate::apps::{AppList, AppUsage};
+use crate::ascii_globe::{body, markers};
+use crate::config::{Config, OpenerConfig, ThemeConfig};
+use crate::notes::{self, Note};
+use crate::projects::{self, Project};
 use crate::recent_files::RecentFiles;
 use crate::system_stats::SystemStats;
-use crate::ui::components::NightSky;
-use crate::ui::Theme;
-use anyhow::Result;
+use crate::ui::components::{item_at, FilePreview, GlobeComponent, NightSky};
+use crate::ui::views::{settings_items, BODY_SETTING, THEME_SETTING};
+use crate::ui::color::{self, ColorSupport};
+use crate::ui::keymap::{Action, Key, Keymap, Resolved};
+use crate::ui::new_file::NewFilePrompt;
+use crate::ui::note_editor::NoteEditor;
+use crate::ui::theme::{self, Theme};
+use anyhow::{Context, Result};
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Longest gap between the clicks of a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// How long a warning stays at the bottom of the screen
+const NOTICE_TIME: Duration = Duration::from_secs(10);
+/// Lines Page Up and Page Down move in the note editor
+const NOTE_PAGE: isize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AppState {
     Home,
     Apps,
     RecentFiles,
     Settings,
+    Notes,
+    Projects,
+}
+
+/// The "open with" chooser for a recent file
+pub struct OpenWithMenu {
+    pub path: PathBuf,
+    /// Matching openers, then `None` for the system opener
+    pub choices: Vec<Option<OpenerConfig>>,
+    pub selected: usize,
+}
+
+/// The commands that can be run in a project
+pub struct ProjectMenu {
+    pub project: Project,
+    pub selected: usize,
 }
 
 pub struct App {
@@ -18,258 +55,713 @@ pub struct App {
     pub config: Config,
     pub recent_files: RecentFiles,
     pub system_stats: SystemStats,
-    pub app_selection: usize,
+    pub globe: GlobeComponent,
+    /// The Apps view's group tabs and the order apps are listed in
+    pub app_list: AppList,
+    app_usage: AppUsage,
     pub recent_selection: Option<usize>,
     pub settings_selection: Option<usize>,
+    /// Notes found when the Notes view was opened
+    pub notes: Vec<Note>,
+    pub note_selection: usize,
+    /// Repositories found when the Projects view was opened
+    pub projects: Vec<Project>,
+    pub project_selection: usize,
     pub should_quit: bool,
-    pub stars: Option<NightSky>,
+    pub stars: NightSky,
+    /// Preview pane in the Recent view, unless turned off
+    pub preview: Option<FilePreview>,
+    /// Colours in use: the `ui.theme` preset or the inline `[theme]`
+    active_theme: ThemeConfig,
+    color_support: Option<ColorSupport>,
+    // Last pointer position of a drag that started on the globe
+    drag_from: Option<(u16, u16)>,
+    pub keymap: Keymap,
+    // Keys of a chord typed so far
+    pending_keys: Vec<Key>,
+    pub show_help: bool,
+    pub open_with: Option<OpenWithMenu>,
+    pub project_menu: Option<ProjectMenu>,
+    pub new_file: Option<NewFilePrompt>,
+    pub note_editor: Option<NoteEditor>,
+    // Warning being shown and when it appeared
+    notice: Option<(String, Instant)>,
+    /// Menu item under the mouse pointer
+    pub hovered: Option<usize>,
+    /// Menu items drawn in the last frame and the cells their labels cover
+    pub menu_items: Vec<(usize, Rect)>,
+    // Time and position of the last click on a menu item
+    last_click: Option<(Instant, (u16, u16))>,
 }
 
 impl App {
     pub fn new() -> Result<Self> {
         let config = Config::load()?;
-        let recent_files = RecentFiles::new()?;
+        let recent_files = RecentFiles::new(&config.recent)?;
         let system_stats = SystemStats::new();
         
+        let texture_dirs = body::texture_dirs(&config.globe.texture_path);
+        let mut globe = GlobeComponent::new(texture_dirs, &config.globe.body)?;
+        globe.set_scale(config.globe.scale);
+        globe.set_speed(config.globe.speed);
+        globe.set_tilt(config.globe.tilt);
+        globe.set_lighting(config.globe.lighting);
+        globe.set_light_source(config.globe.light_source);
+        globe.set_time_lapse(config.globe.time_lapse);
+        globe.set_render_mode(config.globe.render_mode);
+        let (globe_markers, globe_arcs) = markers::from_config(&config.globe);
+        globe.set_markers(globe_markers, globe_arcs);
+        let stars = NightSky::new(config.stars.resolve_seed(), &config.stars);
+        let preview = config.recent.preview.then(|| FilePreview::new(config.recent.preview_lines));
+        let keymap = Keymap::new(&config.keys).context("Invalid [keys] config")?;
+        // A missing or broken theme file falls back to `[theme]` rather than stopping startup
+        let (active_theme, notice) = match &config.ui.theme {
+            Some(name) => match theme::load_theme(name) {
+                Ok(colors) => (colors, None),
+                Err(e) => (config.theme.clone(), Some((format!("{:#}", e), Instant::now()))),
+            },
+            None => (config.theme.clone(), None),
+        };
+        
+        // Other programs' histories are a convenience; a bad one shouldn't stop startup
+        let _ = recent_files.import();
+        
         // Select first item if available
         let files = recent_files.get_files().unwrap_or_default();
         let recent_selection = if !files.is_empty() { Some(0) } else { None };
         
-        // Start app selection in the middle of the list
-        let app_selection = if config.apps.is_empty() {
-            0
-        } else {
-            config.apps.len().saturating_sub(1) / 2
-        };
+        let app_usage = AppUsage::new()?;
+        let app_list = AppList::new(&config.apps, &app_usage);
         
         Ok(Self {
             state: AppState::Home,
             config,
             recent_files,
             system_stats,
-            app_selection,
+            globe,
+            app_list,
+            app_usage,
             recent_selection,
             settings_selection: Some(0),
+            notes: Vec::new(),
+            note_selection: 0,
+            projects: Vec::new(),
+            project_selection: 0,
             should_quit: false,
-            stars: None,
+            stars,
+            preview,
+            active_theme,
+            color_support: color::detect_colors(),
+            drag_from: None,
+            keymap,
+            pending_keys: Vec::new(),
+            show_help: false,
+            open_with: None,
+            project_menu: None,
+            new_file: None,
+            note_editor: None,
+            notice,
+            hovered: None,
+            menu_items: Vec::new(),
+            last_click: None,
         })
     }
 
-    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
-        match key.code {
-            crossterm::event::KeyCode::Char('q') => {
-                self.should_quit = true;
-            }
-            crossterm::event::KeyCode::Char('Q') => {
-                self.should_quit = true;
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return Ok(());
+        }
+        if self.open_with.is_some() {
+            return self.handle_open_with_key(Key::from(key));
+        }
+        if self.project_menu.is_some() {
+            return self.handle_project_menu_key(Key::from(key));
+        }
+        if self.new_file.is_some() {
+            return self.handle_new_file_key(key);
+        }
+        if self.note_editor.is_some() {
+            return self.handle_note_key(key);
+        }
+
+        self.pending_keys.push(Key::from(key));
+        loop {
+            match self.keymap.resolve(self.state, &self.pending_keys) {
+                Resolved::Action(action) => {
+                    self.pending_keys.clear();
+                    return self.perform(action);
+                }
+                Resolved::Pending => return Ok(()),
+                // A broken-off chord: try the last key on its own
+                Resolved::None if self.pending_keys.len() > 1 => {
+                    self.pending_keys.drain(..self.pending_keys.len() - 1);
+                }
+                Resolved::None => {
+                    self.pending_keys.clear();
+                    return Ok(());
+                }
             }
-            crossterm::event::KeyCode::Esc => {
-                // Esc returns home from submenus, quits from home
+        }
+    }
+
+    fn perform(&mut self, action: Action) -> Result<()> {
+        self.hovered = None;
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back => {
+                // Returns home from submenus, quits from home
                 if self.state == AppState::Home {
                     self.should_quit = true;
                 } else {
                     self.state = AppState::Home;
                 }
             }
-            crossterm::event::KeyCode::Char('a') => {
+            Action::GoHome => self.state = AppState::Home,
+            Action::OpenApps => {
                 self.state = AppState::Apps;
-                // Start selection in the middle of the list
-                if !self.config.apps.is_empty() {
-                    self.app_selection = self.config.apps.len().saturating_sub(1) / 2;
-                } else {
-                    self.app_selection = 0;
-                }
+                // Re-sort by usage and start at the top, where the
+                // favourites and most used apps are
+                self.app_list.refresh(&self.config.apps, &self.app_usage);
             }
-            crossterm::event::KeyCode::Char('r') => {
+            Action::OpenRecent => {
                 self.state = AppState::RecentFiles;
-                // Refresh selection when entering RecentFiles view
+                // Pick up files opened elsewhere since startup, then refresh selection
+                let _ = self.recent_files.import();
                 let files = self.recent_files.get_files().unwrap_or_default();
-                if !files.is_empty() {
-                    self.recent_selection = Some(0);
-                } else {
-                    self.recent_selection = None;
-                }
+                self.recent_selection = if files.is_empty() { None } else { Some(0) };
             }
-            crossterm::event::KeyCode::Char('s') => {
+            Action::OpenNotes => {
+                self.state = AppState::Notes;
+                self.refresh_notes();
+                self.note_selection = 0;
+            }
+            Action::OpenProjects => {
+                self.state = AppState::Projects;
+                // Rescan so new clones and branch switches show up
+                self.projects = projects::find(&self.config.projects);
+                self.project_selection = 0;
+            }
+            Action::OpenSettings => {
                 self.state = AppState::Settings;
                 self.settings_selection = Some(0);
             }
-            crossterm::event::KeyCode::Char('h') => {
-                // 'h' returns home from submenus
-                if self.state != AppState::Home {
-                    self.state = AppState::Home;
+            Action::NavigateUp if self.state == AppState::Home => self.globe.nudge(0.0, -1.0),
+            Action::NavigateDown if self.state == AppState::Home => self.globe.nudge(0.0, 1.0),
+            Action::NavigateLeft if self.state == AppState::Home => self.globe.nudge(-1.0, 0.0),
+            Action::NavigateRight if self.state == AppState::Home => self.globe.nudge(1.0, 0.0),
+            Action::NavigateUp => self.move_selection(-1),
+            Action::NavigateDown => self.move_selection(1),
+            Action::NavigateLeft if self.state == AppState::Apps => {
+                self.app_list.switch_group(-1, &self.config.apps, &self.app_usage);
+            }
+            Action::NavigateRight if self.state == AppState::Apps => {
+                self.app_list.switch_group(1, &self.config.apps, &self.app_usage);
+            }
+            Action::NavigateLeft if self.theme_selected() => self.cycle_theme(false),
+            Action::NavigateRight if self.theme_selected() => self.cycle_theme(true),
+            Action::NavigateLeft | Action::NavigateRight => {}
+            Action::Launch => self.launch_selected()?,
+            Action::NewFile if self.state == AppState::Notes => {
+                let path = notes::new_note_path(&self.notes_dir(), Local::now());
+                self.note_editor = Some(NoteEditor::new(path));
+            }
+            Action::NewFile => {
+                self.new_file = Some(NewFilePrompt::new(PathBuf::from(&self.config.ui.default_text_dir)));
+            }
+            Action::CycleBody => self.cycle_body()?,
+            Action::ZoomIn => self.globe.zoom_in(),
+            Action::ZoomOut => self.globe.zoom_out(),
+            Action::TogglePause => {
+                self.globe.toggle_pause();
+            }
+            Action::ResetView => self.globe.reset_view(),
+            Action::RotateLeft => self.globe.nudge(-1.0, 0.0),
+            Action::RotateRight => self.globe.nudge(1.0, 0.0),
+            Action::RotateUp => self.globe.nudge(0.0, -1.0),
+            Action::RotateDown => self.globe.nudge(0.0, 1.0),
+            Action::ToggleHelp => self.show_help = true,
+            Action::TogglePin => {
+                if let Some(file) = self.selected_file()? {
+                    self.recent_files.toggle_pin(&file)?;
                 }
             }
-            crossterm::event::KeyCode::Char('j') => {
-                // 'j' moves down (same as Down arrow)
-                match self.state {
-                    AppState::Apps => {
-                        if self.app_selection < self.config.apps.len().saturating_sub(1) {
-                            self.app_selection += 1;
-                        }
-                    }
-                    AppState::RecentFiles => {
-                        let files = self.recent_files.get_files().unwrap_or_default();
-                        if let Some(selected) = self.recent_selection {
-                            if selected < files.len().saturating_sub(1) {
-                                self.recent_selection = Some(selected + 1);
-                            }
-                        }
-                    }
-                    AppState::Settings => {
-                        if let Some(selected) = self.settings_selection {
-                            if selected < 4 {
-                                self.settings_selection = Some(selected + 1);
-                            }
-                        }
-                    }
-                    _ => {}
+            Action::RemoveFile => {
+                if let Some(file) = self.selected_file()? {
+                    self.recent_files.remove(&file)?;
+                    self.clamp_recent_selection();
                 }
             }
-            crossterm::event::KeyCode::Char('k') => {
-                // 'k' moves up (same as Up arrow)
-                match self.state {
-                    AppState::Apps => {
-                        if self.app_selection > 0 {
-                            self.app_selection -= 1;
-                        }
-                    }
-                    AppState::RecentFiles => {
-                        if let Some(selected) = self.recent_selection {
-                            if selected > 0 {
-                                self.recent_selection = Some(selected - 1);
-                            }
-                        }
-                    }
-                    AppState::Settings => {
-                        if let Some(selected) = self.settings_selection {
-                            if selected > 0 {
-                                self.settings_selection = Some(selected - 1);
-                            }
-                        }
+            Action::ClearRecent => {
+                self.recent_files.clear()?;
+                self.clamp_recent_selection();
+            }
+            Action::OpenWith if self.state == AppState::Projects => {
+                if let Some(project) = self.projects.get(self.project_selection) {
+                    if !self.config.projects.commands.is_empty() {
+                        self.project_menu = Some(ProjectMenu { project: project.clone(), selected: 0 });
                     }
-                    _ => {}
                 }
             }
-            crossterm::event::KeyCode::Char('l') => {
-                // 'l' selects/launches (same as Enter) in submenus
-                match self.state {
-                    AppState::Apps => {
-                        if let Some(app) = self.config.apps.get(self.app_selection) {
-                            crate::launcher::launch_app(app)?;
-                        }
-                    }
-                    AppState::RecentFiles => {
-                        let files = self.recent_files.get_files()?;
-                        if let Some(selected) = self.recent_selection {
-                            if let Some(file) = files.get(selected) {
-                                crate::launcher::open_file(file)?;
-                                self.recent_files.add_file(file.clone())?;
-                            }
-                        }
+            Action::OpenWith => {
+                if let Some(path) = self.selected_file()? {
+                    let openers = crate::launcher::openers_for(&self.config.openers, &path);
+                    let mut choices: Vec<Option<OpenerConfig>> = openers.into_iter().cloned().map(Some).collect();
+                    choices.push(None);
+                    self.open_with = Some(OpenWithMenu { path, choices, selected: 0 });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Type into the new file prompt. Keys go to the text rather than the
+    /// keymap, so the prompt has fixed keys.
+    fn handle_new_file_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.new_file.as_mut() else {
+            return Ok(());
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.new_file = None,
+            KeyCode::Tab => prompt.complete(),
+            KeyCode::Up => prompt.move_template(-1),
+            KeyCode::Down => prompt.move_template(1),
+            KeyCode::Backspace => prompt.backspace(),
+            KeyCode::Char('u') if ctrl => prompt.clear(),
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => prompt.insert(c),
+            KeyCode::Enter => {
+                let now = Local::now();
+                let path = prompt.target(now);
+                let contents = prompt.template().render(&path, now);
+                match crate::launcher::create_file(&path, &contents) {
+                    Ok(()) => {
+                        // The file exists now, so a failure to open it can't be
+                        // fixed in the prompt
+                        self.new_file = None;
+                        self.recent_files.add_file(path.clone())?;
+                        let opened = crate::launcher::open_new_file(&path, &self.config.ui.text_editor, &self.config.openers);
+                        self.notify_failure(opened);
                     }
-                    AppState::Settings => {
-                        // Settings Enter behavior - currently no-op, but 'l' key should work
+                    // Stay open so the name can be fixed
+                    Err(e) => prompt.error = Some(format!("{:#}", e)),
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Type into the note being edited. Like the new file prompt it has
+    /// fixed keys; Esc saves and closes.
+    fn handle_note_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(editor) = self.note_editor.as_mut() else {
+            return Ok(());
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.save_note(true)?,
+            KeyCode::Char('s') if ctrl => self.save_note(false)?,
+            KeyCode::Left => editor.move_left(),
+            KeyCode::Right => editor.move_right(),
+            KeyCode::Up => editor.move_vertical(-1),
+            KeyCode::Down => editor.move_vertical(1),
+            KeyCode::PageUp => editor.move_vertical(-NOTE_PAGE),
+            KeyCode::PageDown => editor.move_vertical(NOTE_PAGE),
+            KeyCode::Home => editor.move_home(),
+            KeyCode::End => editor.move_end(),
+            KeyCode::Enter => editor.newline(),
+            KeyCode::Backspace => editor.backspace(),
+            KeyCode::Delete => editor.delete(),
+            KeyCode::Tab => editor.insert_tab(),
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => editor.insert(c),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Save the note being edited, registering it as a recent file, and
+    /// close the editor if asked. A failed save leaves it open so nothing
+    /// typed is lost.
+    fn save_note(&mut self, close: bool) -> Result<()> {
+        let Some(editor) = self.note_editor.as_mut() else {
+            return Ok(());
+        };
+        match editor.save() {
+            Ok(saved) => {
+                if saved {
+                    self.recent_files.add_file(editor.path.clone())?;
+                    self.refresh_notes();
+                }
+                if close {
+                    self.note_editor = None;
+                }
+            }
+            Err(e) => editor.error = Some(format!("{:#}", e)),
+        }
+        Ok(())
+    }
+
+    /// Where notes are kept
+    fn notes_dir(&self) -> PathBuf {
+        PathBuf::from(&self.config.ui.default_text_dir)
+    }
+
+    fn refresh_notes(&mut self) {
+        self.notes = notes::list_notes(&self.notes_dir());
+        self.note_selection = self.note_selection.min(self.notes.len().saturating_sub(1));
+    }
+
+    /// Move through the "open with" chooser with the list keys; launching
+    /// opens the file and going back closes it
+    fn handle_open_with_key(&mut self, key: Key) -> Result<()> {
+        let Some(menu) = self.open_with.as_mut() else {
+            return Ok(());
+        };
+        let Resolved::Action(action) = self.keymap.resolve(self.state, &[key]) else {
+            return Ok(());
+        };
+        match action {
+            Action::NavigateUp => menu.selected = menu.selected.saturating_sub(1),
+            Action::NavigateDown => menu.selected = (menu.selected + 1).min(menu.choices.len() - 1),
+            Action::Launch => {
+                if let Some(menu) = self.open_with.take() {
+                    let result = match &menu.choices[menu.selected] {
+                        Some(opener) => crate::launcher::open_with(opener, &menu.path),
+                        None => crate::launcher::open_with_system(&menu.path),
+                    };
+                    if self.notify_failure(result) {
+                        self.recent_files.add_file(menu.path)?;
                     }
-                    _ => {}
                 }
             }
-            crossterm::event::KeyCode::Char('n') => {
-                // Create new file - only works from home view
-                if self.state == AppState::Home {
-                    let file_path = crate::launcher::create_and_open_text_file(
+            Action::Back | Action::Quit => self.open_with = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Move through the project's commands like the "open with" chooser
+    fn handle_project_menu_key(&mut self, key: Key) -> Result<()> {
+        let Some(menu) = self.project_menu.as_mut() else {
+            return Ok(());
+        };
+        let Resolved::Action(action) = self.keymap.resolve(self.state, &[key]) else {
+            return Ok(());
+        };
+        let commands = &self.config.projects.commands;
+        match action {
+            Action::NavigateUp => menu.selected = menu.selected.saturating_sub(1),
+            Action::NavigateDown => menu.selected = (menu.selected + 1).min(commands.len() - 1),
+            Action::Launch => {
+                if let Some(menu) = self.project_menu.take() {
+                    let result = crate::launcher::launch_in_project(
+                        &commands[menu.selected],
+                        &menu.project.path,
                         &self.config.ui.text_editor,
-                        &self.config.ui.default_text_dir,
-                    )?;
-                    self.recent_files.add_file(file_path)?;
+                    );
+                    self.notify_failure(result);
                 }
             }
-            crossterm::event::KeyCode::Enter => {
-                match self.state {
-                    AppState::Apps => {
-                        if let Some(app) = self.config.apps.get(self.app_selection) {
-                            crate::launcher::launch_app(app)?;
-                        }
-                    }
-                    AppState::RecentFiles => {
-                        let files = self.recent_files.get_files()?;
-                        if let Some(selected) = self.recent_selection {
-                            if let Some(file) = files.get(selected) {
-                                crate::launcher::open_file(file)?;
-                                self.recent_files.add_file(file.clone())?;
-                            }
-                        }
-                    }
-                    AppState::Home => {
-                        crate::launcher::launch_terminal()?;
+            Action::Back | Action::Quit => self.project_menu = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Move the current view's selection `delta` items, staying in the list
+    fn move_selection(&mut self, delta: isize) {
+        let step = |selected: usize, count: usize| {
+            selected
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1))
+        };
+        match self.state {
+            AppState::Apps => {
+                self.app_list.selected = step(self.app_list.selected, self.app_list.visible.len());
+            }
+            AppState::RecentFiles => {
+                let count = self.recent_files.get_files().unwrap_or_default().len();
+                self.recent_selection = self.recent_selection.map(|s| step(s, count));
+            }
+            AppState::Settings => {
+                let count = self.settings_count();
+                self.settings_selection = self.settings_selection.map(|s| step(s, count));
+            }
+            AppState::Notes => {
+                self.note_selection = step(self.note_selection, self.notes.len());
+            }
+            AppState::Projects => {
+                self.project_selection = step(self.project_selection, self.projects.len());
+            }
+            AppState::Home => {}
+        }
+    }
+
+    /// The selected file in the Recent view
+    fn selected_file(&self) -> Result<Option<PathBuf>> {
+        if self.state != AppState::RecentFiles {
+            return Ok(None);
+        }
+        let files = self.recent_files.get_files()?;
+        Ok(self.recent_selection.and_then(|s| files.get(s).cloned()))
+    }
+
+    /// Keep the Recent selection inside the list after files are removed
+    fn clamp_recent_selection(&mut self) {
+        let count = self.recent_files.get_files().unwrap_or_default().len();
+        self.recent_selection = match count {
+            0 => None,
+            _ => Some(self.recent_selection.unwrap_or(0).min(count - 1)),
+        };
+    }
+
+    /// Launch the selected app, file or project, edit the selected note, change the selected
+    /// setting, or open a terminal from home
+    fn launch_selected(&mut self) -> Result<()> {
+        match self.state {
+            AppState::Apps => {
+                if let Some(app) = self.app_list.selected_app(&self.config.apps) {
+                    let name = app.name.clone();
+                    let result = crate::launcher::launch_app(app);
+                    // The list keeps its order until it's next opened, so
+                    // the selection doesn't jump
+                    if self.notify_failure(result) {
+                        self.app_usage.record(&name)?;
                     }
-                    _ => {}
                 }
             }
-            crossterm::event::KeyCode::Up => {
-                match self.state {
-                    AppState::Apps => {
-                        if self.app_selection > 0 {
-                            self.app_selection -= 1;
-                        }
-                    }
-                    AppState::RecentFiles => {
-                        if let Some(selected) = self.recent_selection {
-                            if selected > 0 {
-                                self.recent_selection = Some(selected - 1);
-                            }
-                        }
+            AppState::RecentFiles => {
+                let files = self.recent_files.get_files()?;
+                if let Some(file) = self.recent_selection.and_then(|s| files.get(s)) {
+                    let result = crate::launcher::open_file(file, &self.config.openers);
+                    if self.notify_failure(result) {
+                        self.recent_files.add_file(file.clone())?;
                     }
-                    AppState::Settings => {
-                        if let Some(selected) = self.settings_selection {
-                            if selected > 0 {
-                                self.settings_selection = Some(selected - 1);
-                            }
-                        }
-                    }
-                    _ => {}
                 }
             }
-            crossterm::event::KeyCode::Down => {
-                match self.state {
-                    AppState::Apps => {
-                        if self.app_selection < self.config.apps.len().saturating_sub(1) {
-                            self.app_selection += 1;
-                        }
+            AppState::Home => {
+                crate::launcher::launch_terminal()?;
+            }
+            AppState::Settings => {
+                self.activate_setting()?;
+            }
+            AppState::Notes => {
+                if let Some(note) = self.notes.get(self.note_selection) {
+                    match NoteEditor::open(note.path.clone()) {
+                        Ok(editor) => self.note_editor = Some(editor),
+                        Err(e) => self.notice = Some((format!("{:#}", e), Instant::now())),
                     }
-                    AppState::RecentFiles => {
-                        let files = self.recent_files.get_files().unwrap_or_default();
-                        if let Some(selected) = self.recent_selection {
-                            if selected < files.len().saturating_sub(1) {
-                                self.recent_selection = Some(selected + 1);
-                            }
-                        }
+                }
+            }
+            AppState::Projects => {
+                // Everything in the menu can be run from there
+                if let Some(project) = self.projects.get(self.project_selection) {
+                    let results: Vec<Result<()>> = self
+                        .config
+                        .projects
+                        .launch_commands()
+                        .into_iter()
+                        .map(|command| crate::launcher::launch_in_project(command, &project.path, &self.config.ui.text_editor))
+                        .collect();
+                    for result in results {
+                        self.notify_failure(result);
                     }
-                    AppState::Settings => {
-                        // 5 settings items
-                        if let Some(selected) = self.settings_selection {
-                            if selected < 4 {
-                                self.settings_selection = Some(selected + 1);
-                            }
-                        }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a mouse event in a terminal of size `area`. Dragging on the
+    /// globe spins it and scrolling over it zooms; on the menu, clicking
+    /// selects an item, double-clicking launches it and scrolling moves the
+    /// selection.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) -> Result<()> {
+        // The overlays are keyboard only
+        if self.open_with.is_some() || self.project_menu.is_some() || self.new_file.is_some() || self.note_editor.is_some() {
+            return Ok(());
+        }
+        let globe_area = globe_area(area);
+        let over_globe = globe_area.contains(ratatui::layout::Position {
+            x: mouse.column,
+            y: mouse.row,
+        });
+        let item = item_at(&self.menu_items, mouse.column, mouse.row);
+
+        match mouse.kind {
+            MouseEventKind::Moved => self.hovered = item,
+            MouseEventKind::Down(MouseButton::Left) if item.is_some() => {
+                let now = Instant::now();
+                let position = (mouse.column, mouse.row);
+                // The list scrolls to the first click, so a double-click is
+                // two clicks on the same spot rather than the same item
+                let double = self.last_click.is_some_and(|(at, pos)| {
+                    pos == position && now.duration_since(at) <= DOUBLE_CLICK
+                });
+                if double {
+                    self.last_click = None;
+                    self.launch_selected()?;
+                } else {
+                    self.last_click = Some((now, position));
+                    if let Some(index) = item {
+                        self.select(index);
                     }
-                    _ => {}
                 }
+                self.hovered = None;
+            }
+            MouseEventKind::Down(MouseButton::Left) if over_globe => {
+                self.drag_from = Some((mouse.column, mouse.row));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((x, y)) = self.drag_from {
+                    let dx = mouse.column as i32 - x as i32;
+                    let dy = mouse.row as i32 - y as i32;
+                    self.globe.drag(dx, dy, globe_area.width, globe_area.height);
+                    self.drag_from = Some((mouse.column, mouse.row));
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.drag_from.is_some() => {
+                self.drag_from = None;
+                self.globe.end_drag();
+            }
+            MouseEventKind::ScrollUp if over_globe => self.globe.zoom_in(),
+            MouseEventKind::ScrollDown if over_globe => self.globe.zoom_out(),
+            MouseEventKind::ScrollUp => {
+                self.move_selection(-1);
+                self.hovered = None;
+            }
+            MouseEventKind::ScrollDown => {
+                self.move_selection(1);
+                self.hovered = None;
             }
             _ => {}
         }
-        
         Ok(())
     }
 
+    fn select(&mut self, index: usize) {
+        match self.state {
+            AppState::Apps => self.app_list.selected = index,
+            AppState::RecentFiles => self.recent_selection = Some(index),
+            AppState::Settings => self.settings_selection = Some(index),
+            AppState::Notes => self.note_selection = index,
+            AppState::Projects => self.project_selection = index,
+            AppState::Home => {}
+        }
+    }
+
+    fn settings_count(&self) -> usize {
+        settings_items(&self.config, self.globe.body_name()).len()
+    }
+
+    fn activate_setting(&mut self) -> Result<()> {
+        match self.settings_selection {
+            Some(BODY_SETTING) => self.cycle_body()?,
+            Some(THEME_SETTING) => self.cycle_theme(true),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn theme_selected(&self) -> bool {
+        self.state == AppState::Settings && self.settings_selection == Some(THEME_SETTING)
+    }
+
+    /// Switch to the next or previous theme, skipping any that fail to
+    /// load. The new colours show on the next frame.
+    fn cycle_theme(&mut self, forward: bool) {
+        let names = theme::theme_names();
+        let current = self
+            .config
+            .ui
+            .theme
+            .as_ref()
+            .and_then(|name| names.iter().position(|n| n == name));
+
+        for step in 1..=names.len() {
+            let index = match current {
+                Some(i) if forward => (i + step) % names.len(),
+                Some(i) => (i + names.len() - step % names.len()) % names.len(),
+                None => step - 1,
+            };
+            if let Ok(colors) = theme::load_theme(&names[index]) {
+                self.active_theme = colors;
+                self.config.ui.theme = Some(names[index].clone());
+                if let Err(e) = Config::save_theme(&names[index]) {
+                    self.notice = Some((format!("Theme not saved: {:#}", e), Instant::now()));
+                }
+                return;
+            }
+        }
+    }
+
+    /// Switch the globe to the next discovered body, skipping any whose
+    /// textures fail to load
+    fn cycle_body(&mut self) -> Result<()> {
+        let bodies = self.globe.available_bodies();
+        let current = self.globe.body_name().to_lowercase();
+        let start = bodies
+            .iter()
+            .position(|b| b.to_lowercase() == current)
+            .unwrap_or(0);
+
+        let mut last_err = None;
+        for step in 1..=bodies.len() {
+            let name = &bodies[(start + step) % bodies.len()];
+            match self.globe.set_body(name) {
+                Ok(()) => {
+                    self.config.globe.body = name.clone();
+                    return Ok(());
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Show the error from `result` as a notice instead of ending the
+    /// session with it. Returns whether `result` was a success.
+    fn notify_failure(&mut self, result: Result<()>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.notice = Some((format!("{:#}", e), Instant::now()));
+                false
+            }
+        }
+    }
+
     pub fn update(&mut self) {
         self.system_stats.refresh();
-        if let Some(ref mut stars) = self.stars {
-            stars.update();
+        self.stars.update(self.globe.rotation());
+
+        if let Some(warning) = self.recent_files.take_warning() {
+            self.notice = Some((warning, Instant::now()));
         }
+        if self.notice.as_ref().is_some_and(|(_, at)| at.elapsed() > NOTICE_TIME) {
+            self.notice = None;
+        }
+    }
+
+    /// Warning to show at the bottom of the screen
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_ref().map(|(message, _)| message.as_str())
     }
 
     pub fn theme(&self) -> Theme {
-        Theme::new(self.config.theme.clone())
+        Theme::new(self.active_theme.clone(), self.color_support)
     }
 }
+
+/// The left half of the screen, where every view draws the globe
+fn globe_area(area: Rect) -> Rect {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area)[0]
+}
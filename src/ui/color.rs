@@ -0,0 +1,211 @@
+use crate::config::RenderMode;
+use ratatui::style::Color;
+use std::env;
+
+/// How many colours the terminal can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorSupport {
+    /// Guess the terminal's colour depth from `COLORTERM` and `TERM`
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or("").to_lowercase();
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            return ColorSupport::TrueColor;
+        }
+        let term = term.unwrap_or("").to_lowercase();
+        if term.contains("direct") {
+            ColorSupport::TrueColor
+        } else if term.contains("256") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Map an RGB colour to the closest colour this terminal can show
+    pub fn quantize(self, rgb: [u8; 3]) -> Color {
+        match self {
+            ColorSupport::TrueColor => Color::Rgb(rgb[0], rgb[1], rgb[2]),
+            ColorSupport::Ansi256 => Color::Indexed(nearest_256(rgb)),
+            ColorSupport::Ansi16 => nearest_16(rgb),
+        }
+    }
+}
+
+/// The terminal's colour support, or `None` if the `NO_COLOR` convention
+/// (https://no-color.org) asks for no colour at all
+pub fn detect_colors() -> Option<ColorSupport> {
+    (!no_color(env::var("NO_COLOR").ok().as_deref())).then(ColorSupport::detect)
+}
+
+/// `NO_COLOR` counts when it's set to anything but an empty string
+fn no_color(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.is_empty())
+}
+
+/// The mode to draw the globe in when the output has `colors`. Half blocks
+/// show the surface only through their colours, so without any they fall
+/// back to the ASCII palette rather than drawing a blank silhouette.
+pub fn mode_for_colors(mode: RenderMode, colors: Option<ColorSupport>) -> RenderMode {
+    match (mode, colors) {
+        (RenderMode::HalfBlock, None) => RenderMode::Ascii,
+        (mode, _) => mode,
+    }
+}
+
+/// Whether the locale allows Unicode output. Only an explicitly configured
+/// non-UTF-8 locale (e.g. `LANG=C`) counts as unsupported.
+pub fn supports_unicode() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty());
+    locale_supports_unicode(locale.as_deref())
+}
+
+fn locale_supports_unicode(locale: Option<&str>) -> bool {
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+// Channel levels of the xterm 6x6x6 colour cube (indices 16-231)
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// Typical xterm values for the 16 ANSI colours
+const ANSI_16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    // Weighted towards green, which the eye is most sensitive to
+    let d = |i: usize, w: i32| {
+        let diff = a[i] as i32 - b[i] as i32;
+        (w * diff * diff) as u32
+    };
+    d(0, 3) + d(1, 4) + d(2, 2)
+}
+
+fn nearest_cube_level(value: u8) -> usize {
+    CUBE_LEVELS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &level)| (level as i32 - value as i32).abs())
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn nearest_256(rgb: [u8; 3]) -> u8 {
+    let (r, g, b) = (
+        nearest_cube_level(rgb[0]),
+        nearest_cube_level(rgb[1]),
+        nearest_cube_level(rgb[2]),
+    );
+    let cube_index = 16 + 36 * r + 6 * g + b;
+    let cube_rgb = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+
+    // Greyscale ramp (indices 232-255) runs from 8 to 238 in steps of 10
+    let average = (rgb[0] as u32 + rgb[1] as u32 + rgb[2] as u32) / 3;
+    let grey_step = (average.saturating_sub(3) / 10).min(23);
+    let grey_value = (8 + grey_step * 10) as u8;
+    let grey_index = 232 + grey_step as usize;
+
+    if distance(rgb, [grey_value; 3]) < distance(rgb, cube_rgb) {
+        grey_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+fn nearest_16(rgb: [u8; 3]) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, value)| distance(rgb, *value))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_env() {
+        assert_eq!(ColorSupport::from_env(Some("truecolor"), Some("xterm")), ColorSupport::TrueColor);
+        assert_eq!(ColorSupport::from_env(Some("24bit"), None), ColorSupport::TrueColor);
+        assert_eq!(ColorSupport::from_env(None, Some("xterm-256color")), ColorSupport::Ansi256);
+        assert_eq!(ColorSupport::from_env(None, Some("screen")), ColorSupport::Ansi16);
+        assert_eq!(ColorSupport::from_env(None, None), ColorSupport::Ansi16);
+    }
+
+    #[test]
+    fn test_no_color() {
+        assert!(no_color(Some("1")));
+        assert!(!no_color(Some("")));
+        assert!(!no_color(None));
+    }
+
+    #[test]
+    fn test_half_block_needs_color() {
+        assert_eq!(mode_for_colors(RenderMode::HalfBlock, None), RenderMode::Ascii);
+        assert_eq!(mode_for_colors(RenderMode::Braille, None), RenderMode::Braille);
+        assert_eq!(
+            mode_for_colors(RenderMode::HalfBlock, Some(ColorSupport::Ansi16)),
+            RenderMode::HalfBlock
+        );
+    }
+
+    #[test]
+    fn test_locale_unicode() {
+        assert!(locale_supports_unicode(Some("en_US.UTF-8")));
+        assert!(locale_supports_unicode(Some("C.utf8")));
+        assert!(locale_supports_unicode(None));
+        assert!(!locale_supports_unicode(Some("C")));
+        assert!(!locale_supports_unicode(Some("en_US.ISO-8859-1")));
+    }
+
+    #[test]
+    fn test_quantize_256() {
+        assert_eq!(ColorSupport::Ansi256.quantize([255, 0, 0]), Color::Indexed(196));
+        assert_eq!(ColorSupport::Ansi256.quantize([0, 0, 0]), Color::Indexed(16));
+        assert_eq!(ColorSupport::Ansi256.quantize([128, 128, 128]), Color::Indexed(244));
+    }
+
+    #[test]
+    fn test_quantize_16() {
+        assert_eq!(ColorSupport::Ansi16.quantize([250, 10, 10]), Color::LightRed);
+        assert_eq!(ColorSupport::Ansi16.quantize([10, 10, 200]), Color::Blue);
+        assert_eq!(ColorSupport::Ansi16.quantize([250, 250, 250]), Color::White);
+    }
+}
@@ -0,0 +1,125 @@
+/// Star from the bundled catalogue: right ascension in hours, declination
+/// in degrees and apparent visual magnitude (smaller is brighter)
+pub struct CatalogueStar {
+    pub ra: f64,
+    pub dec: f64,
+    pub magnitude: f64,
+}
+
+/// A constellation's brightest stars and the usual stick figure joining
+/// them, as pairs of indices into `stars`
+pub struct Constellation {
+    pub stars: &'static [CatalogueStar],
+    pub lines: &'static [(usize, usize)],
+}
+
+const fn star(ra: f64, dec: f64, magnitude: f64) -> CatalogueStar {
+    CatalogueStar { ra, dec, magnitude }
+}
+
+/// J2000 positions of a handful of well-known constellations, taken from
+/// the Yale Bright Star Catalogue
+pub const CONSTELLATIONS: &[Constellation] = &[
+    // Orion
+    Constellation {
+        stars: &[
+            star(5.919, 7.407, 0.50),  // Betelgeuse
+            star(5.242, -8.202, 0.13), // Rigel
+            star(5.419, 6.350, 1.64),  // Bellatrix
+            star(5.796, -9.670, 2.06), // Saiph
+            star(5.679, -1.943, 1.77), // Alnitak
+            star(5.604, -1.202, 1.69), // Alnilam
+            star(5.533, -0.299, 2.23), // Mintaka
+            star(5.585, 9.934, 3.33),  // Meissa
+        ],
+        lines: &[(7, 0), (7, 2), (0, 4), (2, 6), (6, 5), (5, 4), (4, 3), (6, 1)],
+    },
+    // Ursa Major
+    Constellation {
+        stars: &[
+            star(11.062, 61.751, 1.79), // Dubhe
+            star(11.031, 56.382, 2.37), // Merak
+            star(11.897, 53.695, 2.44), // Phecda
+            star(12.257, 57.033, 3.31), // Megrez
+            star(12.900, 55.960, 1.77), // Alioth
+            star(13.399, 54.925, 2.23), // Mizar
+            star(13.792, 49.313, 1.86), // Alkaid
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 0), (3, 4), (4, 5), (5, 6)],
+    },
+    // Cassiopeia
+    Constellation {
+        stars: &[
+            star(0.153, 59.150, 2.28), // Caph
+            star(0.675, 56.537, 2.24), // Schedar
+            star(0.945, 60.717, 2.15), // Gamma Cas
+            star(1.430, 60.235, 2.66), // Ruchbah
+            star(1.907, 63.670, 3.35), // Segin
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 4)],
+    },
+    // Cygnus
+    Constellation {
+        stars: &[
+            star(20.690, 45.280, 1.25), // Deneb
+            star(20.370, 40.257, 2.23), // Sadr
+            star(20.770, 33.970, 2.48), // Gienah
+            star(19.750, 45.131, 2.87), // Delta Cyg
+            star(19.512, 27.960, 3.05), // Albireo
+        ],
+        lines: &[(0, 1), (1, 4), (2, 1), (1, 3)],
+    },
+    // Lyra
+    Constellation {
+        stars: &[
+            star(18.616, 38.784, 0.03), // Vega
+            star(18.746, 37.605, 4.30), // Zeta Lyr
+            star(18.835, 33.363, 3.52), // Sheliak
+            star(18.982, 32.690, 3.25), // Sulafat
+            star(18.908, 36.899, 4.30), // Delta Lyr
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 1)],
+    },
+    // Scorpius
+    Constellation {
+        stars: &[
+            star(16.091, -19.806, 2.62), // Acrab
+            star(16.006, -22.622, 2.29), // Dschubba
+            star(15.981, -26.114, 2.89), // Pi Sco
+            star(16.490, -26.432, 1.06), // Antares
+            star(16.598, -28.216, 2.82), // Tau Sco
+            star(16.836, -34.293, 2.29), // Epsilon Sco
+            star(16.864, -38.047, 3.00), // Mu Sco
+            star(16.910, -42.362, 3.62), // Zeta Sco
+            star(17.202, -43.239, 3.33), // Eta Sco
+            star(17.622, -42.998, 1.86), // Sargas
+            star(17.793, -40.127, 2.99), // Iota Sco
+            star(17.708, -39.030, 2.39), // Kappa Sco
+            star(17.560, -37.104, 1.62), // Shaula
+        ],
+        lines: &[
+            (0, 1),
+            (2, 1),
+            (1, 3),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 8),
+            (8, 9),
+            (9, 10),
+            (10, 11),
+            (11, 12),
+        ],
+    },
+    // Crux
+    Constellation {
+        stars: &[
+            star(12.443, -63.099, 0.77), // Acrux
+            star(12.795, -59.689, 1.25), // Mimosa
+            star(12.519, -57.113, 1.59), // Gacrux
+            star(12.252, -58.749, 2.79), // Delta Cru
+        ],
+        lines: &[(0, 2), (1, 3)],
+    },
+];
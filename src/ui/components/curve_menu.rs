@@ -102,3 +102,40 @@ pub fn calculate_curve_positions(area: Rect) -> Vec<(u16, u16)> {
     }
     positions
 }
+
+/// Item shown in `slot` when item `selected` sits at the cursor, if any
+pub fn slot_item(slot: usize, selected: usize, count: usize) -> Option<usize> {
+    let item = (selected + slot).checked_sub(CURSOR_SLOT)?;
+    (item < count).then_some(item)
+}
+
+/// Item whose label, as drawn at `items`, covers (`column`, `row`)
+pub fn item_at(items: &[(usize, Rect)], column: u16, row: u16) -> Option<usize> {
+    items
+        .iter()
+        .find(|(_, rect)| rect.contains(ratatui::layout::Position { x: column, y: row }))
+        .map(|&(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_hit_testing() {
+        let items = [(3, Rect::new(60, 20, 8, 1)), (4, Rect::new(59, 21, 6, 1))];
+        assert_eq!(item_at(&items, 60, 20), Some(3));
+        assert_eq!(item_at(&items, 67, 20), Some(3));
+        assert_eq!(item_at(&items, 64, 21), Some(4));
+        // Only the label itself counts, not the row either side of it
+        assert_eq!(item_at(&items, 59, 20), None);
+        assert_eq!(item_at(&items, 68, 20), None);
+        assert_eq!(item_at(&items, 65, 21), None);
+
+        // Slots scroll round the selection
+        assert_eq!(slot_item(CURSOR_SLOT, 4, 10), Some(4));
+        assert_eq!(slot_item(CURSOR_SLOT + 2, 4, 10), Some(6));
+        assert_eq!(slot_item(CURSOR_SLOT - 5, 4, 10), None);
+        assert_eq!(slot_item(CURSOR_SLOT + 6, 4, 10), None);
+    }
+}
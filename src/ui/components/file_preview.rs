@@ -0,0 +1,385 @@
+use crate::recent_files::sniff_mime;
+use crate::ui::Theme;
+use chrono::{DateTime, Local};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// Bytes shown in the hex dump of a binary file
+const HEX_BYTES: usize = 256;
+// Most bytes read to count a text file's lines
+const SCAN_BYTES: u64 = 1024 * 1024;
+// Shortest time between reloads of a file that keeps changing, such as a
+// log being written to
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a text token is, for colouring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Plain,
+    Keyword,
+    Str,
+    Number,
+    Comment,
+}
+
+/// Comment marker and keywords of a language family
+struct Syntax {
+    comment: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const C_LIKE: Syntax = Syntax {
+    comment: "//",
+    keywords: &[
+        "fn", "let", "mut", "pub", "use", "mod", "struct", "enum", "impl", "trait", "match", "if", "else",
+        "for", "while", "loop", "return", "break", "continue", "const", "static", "self", "Self", "true",
+        "false", "function", "var", "class", "new", "import", "export", "from", "int", "void", "char",
+        "double", "float", "bool", "public", "private", "package", "func", "type", "interface", "null",
+        "where", "as", "in", "async", "await", "switch", "case", "default",
+    ],
+};
+
+const SCRIPT: Syntax = Syntax {
+    comment: "#",
+    keywords: &[
+        "def", "class", "import", "from", "return", "if", "elif", "else", "for", "while", "in", "not",
+        "and", "or", "True", "False", "None", "with", "as", "lambda", "then", "fi", "do", "done",
+        "esac", "case", "function", "export", "local", "end", "module", "require", "true", "false",
+    ],
+};
+
+const CONFIG: Syntax = Syntax {
+    comment: "#",
+    keywords: &["true", "false", "null", "yes", "no"],
+};
+
+const LUA: Syntax = Syntax {
+    comment: "--",
+    keywords: &[
+        "local", "function", "end", "if", "then", "else", "elseif", "for", "while", "do", "return",
+        "nil", "true", "false", "and", "or", "not",
+    ],
+};
+
+fn syntax_for(path: &Path) -> Option<&'static Syntax> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "rs" | "c" | "h" | "cpp" | "hpp" | "js" | "ts" | "java" | "go" | "swift" | "kt" | "scala"
+        | "php" | "css" | "json" => Some(&C_LIKE),
+        "py" | "sh" | "bash" | "zsh" | "rb" => Some(&SCRIPT),
+        "toml" | "yaml" | "yml" | "ini" | "conf" | "config" => Some(&CONFIG),
+        "lua" => Some(&LUA),
+        _ => None,
+    }
+}
+
+/// Split a line into coloured tokens: comments to the end of the line,
+/// quoted strings, numbers and the language's keywords
+fn tokenize(line: &str, syntax: &Syntax) -> Vec<(Token, String)> {
+    let mut tokens: Vec<(Token, String)> = Vec::new();
+    let mut push = |token: Token, text: &str| match tokens.last_mut() {
+        Some((last, run)) if *last == token => run.push_str(text),
+        _ => tokens.push((token, text.to_string())),
+    };
+
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with(syntax.comment) {
+            push(Token::Comment, rest);
+            break;
+        }
+        let len = if c == '"' || c == '\'' {
+            // Up to the closing quote, skipping escaped ones
+            let mut escaped = false;
+            let end = rest[1..]
+                .char_indices()
+                .find(|&(_, ch)| {
+                    let close = ch == c && !escaped;
+                    escaped = ch == '\\' && !escaped;
+                    close
+                })
+                .map(|(i, _)| i + 2)
+                .unwrap_or(rest.len());
+            push(Token::Str, &rest[..end]);
+            end
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || (ch == '.' && c.is_ascii_digit())))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            let token = if c.is_ascii_digit() {
+                Token::Number
+            } else if syntax.keywords.contains(&word) {
+                Token::Keyword
+            } else {
+                Token::Plain
+            };
+            push(token, word);
+            end
+        } else {
+            push(Token::Plain, &rest[..c.len_utf8()]);
+            c.len_utf8()
+        };
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+#[derive(Debug)]
+enum Contents {
+    /// The first lines and the line count, which only covers the first
+    /// `SCAN_BYTES` when `partial`
+    Text { lines: Vec<String>, line_count: usize, partial: bool },
+    Binary { mime: &'static str, head: Vec<u8> },
+    Unreadable(String),
+}
+
+/// A loaded preview of one file
+#[derive(Debug)]
+struct Preview {
+    path: PathBuf,
+    size: u64,
+    modified: Option<SystemTime>,
+    loaded_at: Instant,
+    contents: Contents,
+}
+
+impl Preview {
+    fn load(path: &Path, max_lines: usize) -> Self {
+        let metadata = fs::metadata(path);
+        let (size, modified) = match &metadata {
+            Ok(m) => (m.len(), m.modified().ok()),
+            Err(_) => (0, None),
+        };
+        let contents = match metadata.and_then(|_| read_contents(path, max_lines, SCAN_BYTES)) {
+            Ok(contents) => contents,
+            Err(e) => Contents::Unreadable(e.to_string()),
+        };
+        Self {
+            path: path.to_path_buf(),
+            size,
+            modified,
+            loaded_at: Instant::now(),
+            contents,
+        }
+    }
+}
+
+fn read_contents(path: &Path, max_lines: usize, scan_bytes: u64) -> std::io::Result<Contents> {
+    let mut head = Vec::with_capacity(HEX_BYTES);
+    File::open(path)?.take(HEX_BYTES as u64).read_to_end(&mut head)?;
+    let mime = sniff_mime(&head);
+    if !mime.starts_with("text/") {
+        return Ok(Contents::Binary { mime, head });
+    }
+
+    let mut lines = Vec::new();
+    let mut line_count = 0;
+    for line in BufReader::new(File::open(path)?.take(scan_bytes)).split(b'\n') {
+        let line = line?;
+        if lines.len() < max_lines {
+            lines.push(String::from_utf8_lossy(&line).trim_end_matches('\r').replace('\t', "    "));
+        }
+        line_count += 1;
+    }
+    let partial = fs::metadata(path)?.len() > scan_bytes;
+    Ok(Contents::Text { lines, line_count, partial })
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Offset, hex bytes and printable characters, `per_line` bytes a line
+fn hex_lines(bytes: &[u8], per_line: usize) -> Vec<String> {
+    bytes
+        .chunks(per_line.max(1))
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:06x}  {:<width$}  {}", i * per_line, hex.join(" "), ascii, width = per_line * 3 - 1)
+        })
+        .collect()
+}
+
+/// Preview pane for the selected recent file. The file is read again only
+/// when the selection or its modified time changes, and at most once every
+/// `RELOAD_INTERVAL` while it keeps changing.
+pub struct FilePreview {
+    max_lines: usize,
+    preview: Option<Preview>,
+}
+
+impl FilePreview {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            max_lines,
+            preview: None,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, path: &Path, theme: &Theme) {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        let stale = self
+            .preview
+            .as_ref()
+            .is_none_or(|p| p.path != path || (p.modified != modified && p.loaded_at.elapsed() >= RELOAD_INTERVAL));
+        if stale {
+            self.preview = Some(Preview::load(path, self.max_lines));
+        }
+        let Some(preview) = &self.preview else {
+            return;
+        };
+
+        let label = Style::default().fg(theme.text_secondary());
+        let value = Style::default().fg(theme.text_primary());
+        let mut details = vec![
+            Span::styled("Size ", label),
+            Span::styled(format_size(preview.size), value),
+        ];
+        if let Some(modified) = preview.modified {
+            let modified: DateTime<Local> = modified.into();
+            details.push(Span::styled("  Modified ", label));
+            details.push(Span::styled(modified.format("%Y-%m-%d %H:%M").to_string(), value));
+        }
+
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let mut lines = Vec::new();
+        match &preview.contents {
+            Contents::Text { lines: text, line_count, partial } => {
+                let line_count = if *partial { format!("{}+", line_count) } else { line_count.to_string() };
+                details.push(Span::styled("  Lines ", label));
+                details.push(Span::styled(line_count, value));
+                lines.push(Line::from(details));
+                lines.push(Line::default());
+
+                let syntax = syntax_for(path);
+                for line in text {
+                    let spans = match syntax {
+                        Some(syntax) => tokenize(line, syntax)
+                            .into_iter()
+                            .map(|(token, text)| Span::styled(text, token_style(token, theme)))
+                            .collect(),
+                        None => vec![Span::styled(line.clone(), value)],
+                    };
+                    lines.push(Line::from(spans));
+                }
+            }
+            Contents::Binary { mime, head } => {
+                lines.push(Line::from(details));
+                lines.push(Line::from(vec![
+                    Span::styled("Type ", label),
+                    Span::styled(*mime, value),
+                ]));
+                lines.push(Line::default());
+                // Whole groups of 8 bytes that fit: offset + 3 per byte + 1 per byte + gaps
+                let per_line = if inner_width >= 6 + 2 + 16 * 3 + 2 + 16 { 16 } else { 8 };
+                for hex in hex_lines(head, per_line) {
+                    lines.push(Line::from(Span::styled(hex, label)));
+                }
+            }
+            Contents::Unreadable(error) => {
+                lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(theme.status_error()))));
+            }
+        }
+
+        let title = path.file_name().and_then(|n| n.to_str()).unwrap_or("Preview");
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", title))
+            .style(Style::default().fg(theme.border()));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn token_style(token: Token, theme: &Theme) -> Style {
+    match token {
+        Token::Plain => Style::default().fg(theme.text_primary()),
+        Token::Keyword => Style::default().fg(theme.text_accent()).add_modifier(Modifier::BOLD),
+        Token::Str => Style::default().fg(theme.status_good()),
+        Token::Number => Style::default().fg(theme.status_warning()),
+        Token::Comment => Style::default().fg(theme.text_secondary()).add_modifier(Modifier::ITALIC),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize(r#"let s = "a \"b\""; // 42"#, &C_LIKE);
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Keyword, "let".to_string()),
+                (Token::Plain, " s = ".to_string()),
+                (Token::Str, r#""a \"b\"""#.to_string()),
+                (Token::Plain, "; ".to_string()),
+                (Token::Comment, "// 42".to_string()),
+            ]
+        );
+        let tokens = tokenize("x = 3.5 # if", &SCRIPT);
+        assert_eq!(tokens[1], (Token::Number, "3.5".to_string()));
+        assert_eq!(tokens[3], (Token::Comment, "# if".to_string()));
+    }
+
+    #[test]
+    fn test_format_size_and_hex() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(hex_lines(b"PK\x03\x04ab", 4), ["000000  50 4b 03 04  PK..", "000004  61 62        ab"]);
+    }
+
+    #[test]
+    fn test_load_preview() {
+        let dir = TempDir::new("preview");
+        let text = dir.join("notes.md");
+        let binary = dir.join("image.png");
+        fs::write(&text, "one\ntwo\nthree\n").unwrap();
+        fs::write(&binary, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let text = Preview::load(&text, 2);
+        let binary = Preview::load(&binary, 2);
+        let missing = Preview::load(&dir.join("missing.txt"), 2);
+        // Big files only have their start counted
+        let capped = read_contents(&dir.join("notes.md"), 2, 6).unwrap();
+
+        assert_eq!(text.size, 14);
+        match text.contents {
+            Contents::Text { lines, line_count, partial } => {
+                assert_eq!(lines, ["one", "two"]);
+                assert_eq!(line_count, 3);
+                assert!(!partial);
+            }
+            other => panic!("expected text, got {:?}", other),
+        }
+        assert!(matches!(capped, Contents::Text { line_count: 2, partial: true, .. }));
+        assert!(matches!(binary.contents, Contents::Binary { mime: "image/png", .. }));
+        assert!(matches!(missing.contents, Contents::Unreadable(_)));
+    }
+}
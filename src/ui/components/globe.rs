@@ -1,23 +1,86 @@
+use crate::ascii_globe::body;
+use crate::ascii_globe::camera::Cell;
+use crate::ascii_globe::markers::{Arc, Marker};
+use crate::ascii_globe::renderer::FrameKey;
 use crate::ascii_globe::GlobeRenderer;
+use crate::config::{LightSource, RenderMode};
+use crate::ui::color::{self, ColorSupport};
 use anyhow::Result;
+use ratatui::style::Style;
+use std::path::PathBuf;
 use std::time::Instant;
 
+// Rotation per arrow key press, in radians
+const KEY_STEP: f64 = 0.2;
+// Zoom multiplier per scroll notch or +/- press
+const ZOOM_STEP: f64 = 1.1;
+// Every view draws the globe a little larger than the configured scale
+const VIEW_SCALE: f64 = 1.2;
+
+/// A rendered globe cell, styled for the current terminal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlobeCell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl GlobeCell {
+    const BLANK: GlobeCell = GlobeCell { ch: ' ', style: Style::new() };
+
+    /// Whether anything is drawn in this cell
+    pub fn is_blank(&self) -> bool {
+        self.ch == ' ' && self.style.bg.is_none()
+    }
+}
+
 pub struct GlobeComponent {
     renderer: GlobeRenderer,
+    texture_dirs: Vec<PathBuf>,
     last_update: Instant,
-    frame_buffer: Vec<Vec<char>>,
+    // `None` when `NO_COLOR` is set
+    color_support: Option<ColorSupport>,
+    canvas: Vec<Vec<Cell>>,
+    frame_buffer: Vec<Vec<GlobeCell>>,
+    // Key of the frame currently in `frame_buffer`
+    frame_key: Option<FrameKey>,
 }
 
 impl GlobeComponent {
-    pub fn new(texture_dir: &str) -> Result<Self> {
-        let renderer = GlobeRenderer::new(texture_dir)?;
+    pub fn new(texture_dirs: Vec<PathBuf>, body_name: &str) -> Result<Self> {
+        let body = body::find(&texture_dirs, body_name)?;
         Ok(Self {
-            renderer,
+            renderer: GlobeRenderer::new(body),
+            texture_dirs,
             last_update: Instant::now(),
+            color_support: color::detect_colors(),
+            canvas: Vec::new(),
             frame_buffer: Vec::new(),
+            frame_key: None,
         })
     }
 
+    /// Names of every body found in the texture directories
+    pub fn available_bodies(&self) -> Vec<String> {
+        body::discover(&self.texture_dirs)
+            .into_iter()
+            .map(|m| m.name)
+            .collect()
+    }
+
+    pub fn set_body(&mut self, name: &str) -> Result<()> {
+        let body = body::find(&self.texture_dirs, name)?;
+        self.renderer.set_body(body);
+        Ok(())
+    }
+
+    pub fn body_name(&self) -> &str {
+        self.renderer.body_name()
+    }
+
+    pub fn rotation(&self) -> f64 {
+        self.renderer.rotation()
+    }
+
     pub fn update(&mut self) -> Result<()> {
         let now = Instant::now();
         let delta_time = now.duration_since(self.last_update).as_secs_f64();
@@ -27,26 +90,50 @@ impl GlobeComponent {
         Ok(())
     }
 
-    pub fn render(&mut self, width: usize, height: usize) -> Result<&[Vec<char>]> {
-        // Resize frame buffer if needed
-        if self.frame_buffer.len() != height || 
-           self.frame_buffer.first().map(|r| r.len()) != Some(width) {
-            self.frame_buffer = vec![vec![' '; width]; height];
+    /// Render the globe into a `width` x `height` frame. Rendering again
+    /// before anything has changed returns the previous frame.
+    pub fn render(&mut self, width: usize, height: usize) -> Result<&[Vec<GlobeCell>]> {
+        let key = self.renderer.frame_key(width, height);
+        if self.frame_key == Some(key) {
+            return Ok(&self.frame_buffer);
+        }
+        self.frame_key = Some(key);
+        
+        // Resize buffers if needed
+        if self.canvas.len() != height || 
+           self.canvas.first().map(|r| r.len()) != Some(width) {
+            self.canvas = vec![vec![Cell::BLANK; width]; height];
+            self.frame_buffer = vec![vec![GlobeCell::BLANK; width]; height];
         }
         
-        // Clear buffer
-        for row in &mut self.frame_buffer {
-            row.fill(' ');
+        // Clear canvas
+        for row in &mut self.canvas {
+            row.fill(Cell::BLANK);
         }
         
         // Render globe
-        self.renderer.render_frame(&mut self.frame_buffer, width, height);
+        self.renderer.render_frame(&mut self.canvas, width, height);
+        
+        // Resolve colours for this terminal
+        let support = self.color_support;
+        for (cells, styled) in self.canvas.iter().zip(self.frame_buffer.iter_mut()) {
+            for (cell, out) in cells.iter().zip(styled.iter_mut()) {
+                let mut style = Style::default();
+                if let (Some(fg), Some(support)) = (cell.fg, support) {
+                    style = style.fg(support.quantize(fg));
+                }
+                if let (Some(bg), Some(support)) = (cell.bg, support) {
+                    style = style.bg(support.quantize(bg));
+                }
+                *out = GlobeCell { ch: cell.ch, style };
+            }
+        }
         
         Ok(&self.frame_buffer)
     }
 
     pub fn set_scale(&mut self, scale: f64) {
-        self.renderer.set_scale(scale);
+        self.renderer.set_scale(scale * VIEW_SCALE);
     }
 
     pub fn set_speed(&mut self, speed: f64) {
@@ -61,7 +148,60 @@ impl GlobeComponent {
         self.renderer.set_lighting(lighting);
     }
 
-    pub fn get_scale(&self) -> f64 {
-        self.renderer.get_scale()
+    /// Select the rasteriser. Unicode modes fall back to the ASCII palette
+    /// when the locale can't display them, and half blocks do when colour
+    /// is off.
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        let render_mode = match color::mode_for_colors(render_mode, self.color_support) {
+            RenderMode::HalfBlock | RenderMode::Braille if !color::supports_unicode() => RenderMode::Ascii,
+            mode => mode,
+        };
+        self.renderer.set_render_mode(render_mode);
+    }
+
+    pub fn set_markers(&mut self, markers: Vec<Marker>, arcs: Vec<Arc>) {
+        self.renderer.set_markers(markers, arcs);
+    }
+
+    pub fn set_light_source(&mut self, light_source: LightSource) {
+        self.renderer.set_light_source(light_source);
+    }
+
+    pub fn set_time_lapse(&mut self, time_lapse: f64) {
+        self.renderer.set_time_lapse(time_lapse);
+    }
+
+    /// Rotate by a key press step; `right`/`down` are -1, 0 or 1
+    pub fn nudge(&mut self, right: f64, down: f64) {
+        self.renderer.nudge(right * KEY_STEP, down * KEY_STEP);
+    }
+
+    /// Rotate with a mouse drag of (`dx`, `dy`) cells inside a globe area of
+    /// `width` x `height` cells. Dragging across the whole area turns the
+    /// globe half way round.
+    pub fn drag(&mut self, dx: i32, dy: i32, width: u16, height: u16) {
+        let right = dx as f64 / width.max(1) as f64 * std::f64::consts::PI;
+        let down = dy as f64 / height.max(1) as f64 * std::f64::consts::PI;
+        self.renderer.drag(right, down);
+    }
+
+    pub fn end_drag(&mut self) {
+        self.renderer.end_drag();
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.renderer.zoom_by(ZOOM_STEP);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.renderer.zoom_by(1.0 / ZOOM_STEP);
+    }
+
+    pub fn toggle_pause(&mut self) -> bool {
+        self.renderer.toggle_pause()
+    }
+
+    pub fn reset_view(&mut self) {
+        self.renderer.reset_view();
     }
 }
@@ -1,10 +1,13 @@
 pub mod globe;
+pub mod constellations;
 pub mod curved_menu;
 pub mod curve_menu;
+pub mod file_preview;
 pub mod progress_bar;
 pub mod stars;
 
 pub use globe::GlobeComponent;
+pub use file_preview::FilePreview;
 pub use progress_bar::render_vertical_progress_bar;
-pub use curve_menu::{calculate_curve_positions, CURSOR_SLOT};
+pub use curve_menu::{calculate_curve_positions, item_at, slot_item, CURSOR_SLOT};
 pub use stars::NightSky;
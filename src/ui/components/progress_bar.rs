@@ -1,4 +1,4 @@
-use ratatui::style::{Style, Color};
+use ratatui::style::Color;
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use crate::ui::Theme;
@@ -19,14 +19,8 @@ pub fn render_progress_bar(
         0.0
     };
     
-    // Determine color based on percentage
-    let bar_color = if percentage < 50.0 {
-        theme.status_good()
-    } else if percentage < 80.0 {
-        theme.status_warning()
-    } else {
-        theme.status_error()
-    };
+    // Determine style based on percentage
+    let bar_style = theme.level_style(percentage);
     
     // Calculate bar width (leave space for label and percentage)
     let label_width = label.len() as u16 + 1; // +1 for space
@@ -50,7 +44,7 @@ pub fn render_progress_bar(
         area.x,
         area.y,
         &truncated_line,
-        Style::default().fg(bar_color),
+        bar_style,
     );
 }
 
@@ -77,14 +71,8 @@ pub fn render_vertical_progress_bar(
     // Calculate filled height (from bottom up)
     let filled_height = (area.height as f64 * clamped_percentage / 100.0) as u16;
     
-    // Determine color based on percentage
-    let bar_color = if clamped_percentage < 50.0 {
-        theme.status_good()
-    } else if clamped_percentage < 80.0 {
-        theme.status_warning()
-    } else {
-        theme.status_error()
-    };
+    // Determine style based on percentage
+    let bar_style = theme.level_style(clamped_percentage);
     
     // Render filled portion from bottom
     let start_y = area.y + area.height - filled_height;
@@ -97,7 +85,7 @@ pub fn render_vertical_progress_bar(
                     x,
                     y,
                     block_char,
-                    Style::default().fg(bar_color),
+                    bar_style,
                 );
             }
         }
@@ -1,89 +1,179 @@
 use ratatui::layout::Rect;
-use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
+use std::f64::consts::{PI, TAU};
 use std::time::Instant;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::config::StarsConfig;
+use crate::ui::components::constellations::CONSTELLATIONS;
 use crate::ui::Theme;
 
+/// The constellation sky is this many screens wide, so a screen shows 8
+/// hours of right ascension
+const SKY_SCREENS: f64 = 3.0;
+/// Declinations shown at the top and bottom of the screen
+const DEC_TOP: f64 = 70.0;
+const DEC_BOTTOM: f64 = -65.0;
+/// Average seconds between shooting stars
+const METEOR_INTERVAL: f64 = 12.0;
+/// Cells in a shooting star's tail, behind the head
+const METEOR_TAIL: usize = 4;
+/// Size of the star pool; larger skies show more of it
+const MAX_STARS: usize = 500;
+
+/// Background star at a fraction of the sky's width and height
 pub struct Star {
-    x: u16,
-    y: u16,
+    x: f64,
+    y: f64,
     brightness: u8,
     twinkle_speed: f32,
 }
 
+/// Shooting star in screen fractions, moving at (`vx`, `vy`) screens per
+/// second until it burns out after `life` seconds
+struct ShootingStar {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    age: f64,
+    life: f64,
+}
+
+/// A star glyph at a cell of the sky
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkyCell {
+    pub x: u16,
+    pub y: u16,
+    pub glyph: &'static str,
+    pub brightness: u8,
+}
+
 pub struct NightSky {
     stars: Vec<Star>,
+    meteors: Vec<ShootingStar>,
+    options: StarsConfig,
+    rng: StdRng,
+    /// Right ascension (hours) at the left edge before any drift
+    view_ra: f64,
+    /// Parallax offset in screens, grows as the globe turns
+    drift: f64,
+    last_rotation: Option<f64>,
     start_time: Instant,
-    pub initialized_width: u16,
-    pub initialized_height: u16,
+    last_update: Instant,
 }
 
 impl NightSky {
-    pub fn new(width: u16, height: u16) -> Self {
-        let mut rng = rand::thread_rng();
-        
-        // Calculate star count: approximately 1 star per 20 cells, capped at 500
-        let star_count = ((width as usize * height as usize) / 20).min(500);
-        
-        let mut stars = Vec::with_capacity(star_count);
-        
-        for _ in 0..star_count {
+    /// Sky generated from `seed`. Stars are placed independently of the
+    /// screen size so a resize just rescales them.
+    pub fn new(seed: u64, options: &StarsConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut stars = Vec::with_capacity(MAX_STARS);
+
+        for _ in 0..MAX_STARS {
             stars.push(Star {
-                x: rng.gen_range(0..width),
-                y: rng.gen_range(0..height),
+                x: rng.gen_range(0.0..1.0),
+                y: rng.gen_range(0.0..1.0),
                 brightness: rng.gen_range(1..=5),
                 twinkle_speed: rng.gen_range(0.1..=0.5),
             });
         }
-        
+
+        let view_ra = rng.gen_range(0.0..24.0);
+
         Self {
             stars,
+            meteors: Vec::new(),
+            options: options.clone(),
+            rng,
+            view_ra,
+            drift: 0.0,
+            last_rotation: None,
             start_time: Instant::now(),
-            initialized_width: width,
-            initialized_height: height,
+            last_update: Instant::now(),
         }
     }
-    
-    pub fn update(&mut self) {
-        // Time-based animation - no need to do anything here
-        // We'll use elapsed time in render()
+
+    /// Advance shooting stars and drift the sky along with the globe, whose
+    /// spin is `rotation` radians
+    pub fn update(&mut self, rotation: f64) {
+        let now = Instant::now();
+        let delta_time = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+        self.advance(delta_time, rotation);
     }
-    
-    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
-        self.render_with_occupied_positions(frame, area, &std::collections::HashSet::new(), theme);
+
+    fn advance(&mut self, delta_time: f64, rotation: f64) {
+        if let Some(last) = self.last_rotation {
+            // Shortest way round, so wrapping from 2π to 0 isn't a jump
+            let delta = (rotation - last + PI).rem_euclid(TAU) - PI;
+            self.drift = (self.drift + delta / TAU * self.options.parallax).rem_euclid(SKY_SCREENS);
+        }
+        self.last_rotation = Some(rotation);
+
+        for meteor in &mut self.meteors {
+            meteor.age += delta_time;
+        }
+        self.meteors.retain(|m| m.age < m.life);
+
+        if self.options.shooting_stars && self.rng.gen_bool((delta_time / METEOR_INTERVAL).clamp(0.0, 1.0)) {
+            let direction = if self.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            self.meteors.push(ShootingStar {
+                x: self.rng.gen_range(0.1..0.9),
+                y: self.rng.gen_range(0.0..0.4),
+                vx: direction * self.rng.gen_range(0.3..0.6),
+                vy: self.rng.gen_range(0.15..0.3),
+                age: 0.0,
+                life: self.rng.gen_range(0.5..1.0),
+            });
+        }
     }
-    
+
     pub fn render_with_occupied_positions(&mut self, frame: &mut Frame, area: Rect, occupied_positions: &std::collections::HashSet<(u16, u16)>, theme: &Theme) {
         if area.width == 0 || area.height == 0 {
             return;
         }
-        
-        // Reinitialize stars if area dimensions don't match initialized dimensions
-        if area.width != self.initialized_width || area.height != self.initialized_height {
-            *self = Self::new(area.width, area.height);
-        }
-        
+
         let elapsed = self.start_time.elapsed().as_secs_f64();
-        
-        for star in &self.stars {
-            // Bounds checking - ensure star is within the render area
-            if star.x >= area.width || star.y >= area.height {
-                continue;
-            }
-            
+
+        for cell in self.cells(area.width, area.height, elapsed) {
             // Calculate absolute position
-            let abs_x = area.x.saturating_add(star.x);
-            let abs_y = area.y.saturating_add(star.y);
-            
+            let abs_x = area.x.saturating_add(cell.x);
+            let abs_y = area.y.saturating_add(cell.y);
+
             // Skip if this position is occupied (globe or app text)
             if occupied_positions.contains(&(abs_x, abs_y)) {
                 continue;
             }
-            
-            
+
+            // Map brightness to theme style
+            let style = theme.star_style(cell.brightness);
+
+            let star_area = Rect {
+                x: abs_x,
+                y: abs_y,
+                width: 1,
+                height: 1,
+            };
+            let star_widget = Paragraph::new(Line::from(Span::styled(cell.glyph, style)));
+            frame.render_widget(star_widget, star_area);
+        }
+    }
+
+    /// Everything to draw on a `width` x `height` sky `elapsed` seconds
+    /// after it was created, back to front
+    pub fn cells(&self, width: u16, height: u16, elapsed: f64) -> Vec<SkyCell> {
+        // Show as much of the pool as the density asks for at this size
+        let star_count = ((width as f64 * height as f64 * self.options.density) as usize).min(self.stars.len());
+        let mut cells = Vec::with_capacity(star_count);
+
+        for star in &self.stars[..star_count] {
+            let x = ((star.x - self.drift).rem_euclid(1.0) * width as f64) as u16;
+            let y = (star.y * height as f64) as u16;
+
             // Calculate twinkling effect using sine wave
             // Use elapsed time directly with twinkle_speed as a slow multiplier
             // twinkle_speed (0.1-0.5) controls how fast each star twinkles
@@ -91,39 +181,175 @@ impl NightSky {
             let twinkle_raw = (elapsed * star.twinkle_speed as f64 * 0.5).sin();
             // Map sin(-1 to 1) to (0.0 to 1.8) - stars dim to invisible and brighten significantly beyond base
             let twinkle = ((twinkle_raw + 1.0) / 2.0) * 1.8;
-            let current_brightness = (star.brightness as f32 * twinkle as f32).clamp(0.0, 6.0) as u8;
-            
+            let brightness = (star.brightness as f32 * twinkle as f32).clamp(0.0, 6.0) as u8;
+
             // Map brightness to character
-            let star_char = if current_brightness <= 1 {
+            let glyph = if brightness <= 1 {
                 "·"
-            } else if current_brightness <= 3 {
+            } else if brightness <= 3 {
                 "•"
             } else {
                 "✦"
             };
-            
-            // Map brightness to theme color
-            let color = theme.star_color(current_brightness);
-            
-            // Render star at its position
-            let star_area = Rect {
-                x: abs_x,
-                y: abs_y,
-                width: 1,
-                height: 1,
-            };
-            
-            // Ensure we don't overflow
-            if star_area.x < area.x.saturating_add(area.width) && 
-               star_area.y < area.y.saturating_add(area.height) {
-                let star_widget = Paragraph::new(Line::from(Span::styled(star_char, Style::default().fg(color))));
-                frame.render_widget(star_widget, star_area);
+
+            cells.push(SkyCell {
+                x: x.min(width - 1),
+                y: y.min(height - 1),
+                glyph,
+                brightness,
+            });
+        }
+
+        if self.options.constellations {
+            self.constellation_cells(width, height, &mut cells);
+        }
+        self.meteor_cells(width, height, &mut cells);
+        cells
+    }
+
+    /// Screen position in cells of a catalogue star, before clipping
+    fn sky_position(&self, ra: f64, dec: f64, width: u16, height: u16) -> (f64, f64) {
+        // Right ascension grows eastwards, to the left when facing south
+        let screens = ((self.view_ra - ra).rem_euclid(24.0) / 24.0 * SKY_SCREENS - self.drift).rem_euclid(SKY_SCREENS);
+        let y = (DEC_TOP - dec) / (DEC_TOP - DEC_BOTTOM) * height as f64;
+        (screens * width as f64, y)
+    }
+
+    fn constellation_cells(&self, width: u16, height: u16, cells: &mut Vec<SkyCell>) {
+        let on_screen = |x: f64, y: f64| x >= 0.0 && y >= 0.0 && x < width as f64 && y < height as f64;
+
+        for constellation in CONSTELLATIONS {
+            let positions: Vec<(f64, f64)> = constellation
+                .stars
+                .iter()
+                .map(|s| self.sky_position(s.ra, s.dec, width, height))
+                .map(|(x, y)| (x.floor(), y.floor()))
+                .collect();
+
+            // Dotted stick figure first so the stars sit on top
+            for &(a, b) in constellation.lines {
+                let (ax, ay) = positions[a];
+                let (bx, by) = positions[b];
+                // Skip lines broken by the sky wrapping round
+                if (ax - bx).abs() > width as f64 / 2.0 {
+                    continue;
+                }
+                let steps = (ax - bx).abs().max((ay - by).abs()) as usize;
+                for i in 1..steps {
+                    let t = i as f64 / steps as f64;
+                    let (x, y) = ((ax + (bx - ax) * t).round(), (ay + (by - ay) * t).round());
+                    if on_screen(x, y) {
+                        cells.push(SkyCell { x: x as u16, y: y as u16, glyph: "·", brightness: 0 });
+                    }
+                }
+            }
+
+            for (star, &(x, y)) in constellation.stars.iter().zip(&positions) {
+                if !on_screen(x, y) {
+                    continue;
+                }
+                let (glyph, brightness) = match star.magnitude {
+                    m if m < 1.0 => ("✦", 5),
+                    m if m < 2.0 => ("✦", 4),
+                    m if m < 3.0 => ("•", 3),
+                    _ => ("•", 2),
+                };
+                cells.push(SkyCell { x: x as u16, y: y as u16, glyph, brightness });
             }
         }
     }
-    
-    pub fn resize(&mut self, width: u16, height: u16) {
-        // Reinitialize stars when terminal is resized
-        *self = Self::new(width, height);
+
+    fn meteor_cells(&self, width: u16, height: u16, cells: &mut Vec<SkyCell>) {
+        for meteor in &self.meteors {
+            let head_x = (meteor.x + meteor.vx * meteor.age) * width as f64;
+            let head_y = (meteor.y + meteor.vy * meteor.age) * height as f64;
+            // One cell per step back along the path, fading towards the tail
+            let (dx, dy) = (meteor.vx * width as f64, meteor.vy * height as f64);
+            let length = dx.hypot(dy).max(f64::EPSILON);
+            for i in (0..=METEOR_TAIL).rev() {
+                let x = head_x - dx / length * i as f64;
+                let y = head_y - dy / length * i as f64;
+                if x < 0.0 || y < 0.0 || x >= width as f64 || y >= height as f64 {
+                    continue;
+                }
+                let (glyph, brightness) = match i {
+                    0 => ("✦", 5),
+                    1 => ("•", 4),
+                    _ => ("·", (METEOR_TAIL + 1 - i) as u8),
+                };
+                cells.push(SkyCell { x: x as u16, y: y as u16, glyph, brightness });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> StarsConfig {
+        StarsConfig {
+            shooting_stars: false,
+            ..StarsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_and_size_gives_same_sky() {
+        let a = NightSky::new(7, &options());
+        let b = NightSky::new(7, &options());
+        let c = NightSky::new(8, &options());
+        assert_eq!(a.cells(120, 40, 3.0), b.cells(120, 40, 3.0));
+        assert_ne!(a.cells(120, 40, 3.0), c.cells(120, 40, 3.0));
+    }
+
+    #[test]
+    fn test_resize_rescales_sky() {
+        let sky = NightSky::new(7, &StarsConfig { constellations: false, ..options() });
+        let small = sky.cells(100, 30, 0.0);
+        let large = sky.cells(200, 60, 0.0);
+        // Same stars, more of them on the bigger screen
+        assert_eq!(small.len(), 150);
+        assert_eq!(large.len(), 500);
+        for (s, l) in small.iter().zip(&large) {
+            assert!(l.x / 2 == s.x && l.y / 2 == s.y, "{:?} vs {:?}", s, l);
+        }
+    }
+
+    #[test]
+    fn test_orion_belt_is_a_row() {
+        let mut sky = NightSky::new(1, &options());
+        // Put Orion in the middle of the screen
+        sky.view_ra = 5.6 + 4.0;
+        let belt: Vec<(f64, f64)> = [(5.679, -1.943), (5.604, -1.202), (5.533, -0.299)]
+            .iter()
+            .map(|&(ra, dec)| sky.sky_position(ra, dec, 240, 135))
+            .collect();
+        // Alnitak is east of Mintaka, so further left
+        assert!(belt[0].0 < belt[1].0 && belt[1].0 < belt[2].0);
+        assert!((belt[0].1 - belt[2].1).abs() < 2.0);
+        assert!((belt[1].0 - 120.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_drift_follows_rotation_across_wrap() {
+        let mut sky = NightSky::new(3, &options());
+        sky.advance(0.0, TAU - 0.1);
+        sky.advance(0.0, 0.1);
+        let expected = 0.2 / TAU * sky.options.parallax;
+        assert!((sky.drift - expected).abs() < 1e-9, "{}", sky.drift);
+    }
+
+    #[test]
+    fn test_shooting_star_burns_out() {
+        let mut sky = NightSky::new(5, &StarsConfig::default());
+        sky.advance(METEOR_INTERVAL, 0.0);
+        assert_eq!(sky.meteors.len(), 1);
+        let mut cells = Vec::new();
+        sky.meteor_cells(100, 30, &mut cells);
+        assert!(!cells.is_empty());
+        sky.options.shooting_stars = false;
+        sky.advance(1.0, 0.0);
+        assert!(sky.meteors.is_empty());
     }
 }
@@ -0,0 +1,420 @@
+use crate::config::KeysConfig;
+use crate::ui::AppState;
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Something a key can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    Quit,
+    Back,
+    GoHome,
+    OpenApps,
+    OpenRecent,
+    OpenNotes,
+    OpenProjects,
+    OpenSettings,
+    NavigateUp,
+    NavigateDown,
+    NavigateLeft,
+    NavigateRight,
+    Launch,
+    NewFile,
+    CycleBody,
+    ZoomIn,
+    ZoomOut,
+    TogglePause,
+    ResetView,
+    RotateLeft,
+    RotateRight,
+    RotateUp,
+    RotateDown,
+    ToggleHelp,
+    TogglePin,
+    RemoveFile,
+    ClearRecent,
+    OpenWith,
+}
+
+/// Every action with its name in the `[keys]` config and its help text,
+/// in the order the help overlay lists them
+const ACTIONS: &[(Action, &str, &str)] = &[
+    (Action::NavigateUp, "navigate_up", "Move up"),
+    (Action::NavigateDown, "navigate_down", "Move down"),
+    (Action::NavigateLeft, "navigate_left", "Move left / previous value or group"),
+    (Action::NavigateRight, "navigate_right", "Move right / next value or group"),
+    (Action::Launch, "launch", "Launch or open the selection"),
+    (Action::NewFile, "new_file", "Create a new file or note"),
+    (Action::TogglePin, "toggle_pin", "Pin or unpin the selected file"),
+    (Action::RemoveFile, "remove_file", "Remove the selected file from the list"),
+    (Action::ClearRecent, "clear_recent", "Forget all unpinned files"),
+    (Action::OpenWith, "open_with", "Choose what opens the selection"),
+    (Action::OpenApps, "open_apps", "Show apps"),
+    (Action::OpenRecent, "open_recent", "Show recent files"),
+    (Action::OpenNotes, "open_notes", "Show notes"),
+    (Action::OpenProjects, "open_projects", "Show projects"),
+    (Action::OpenSettings, "open_settings", "Show settings"),
+    (Action::GoHome, "go_home", "Go home"),
+    (Action::Back, "back", "Go home, or quit from home"),
+    (Action::Quit, "quit", "Quit"),
+    (Action::CycleBody, "cycle_body", "Next globe body"),
+    (Action::ZoomIn, "zoom_in", "Zoom the globe in"),
+    (Action::ZoomOut, "zoom_out", "Zoom the globe out"),
+    (Action::TogglePause, "toggle_pause", "Pause or resume the globe"),
+    (Action::ResetView, "reset_view", "Reset the globe view"),
+    (Action::RotateLeft, "rotate_left", "Turn the globe left"),
+    (Action::RotateRight, "rotate_right", "Turn the globe right"),
+    (Action::RotateUp, "rotate_up", "Tilt the globe up"),
+    (Action::RotateDown, "rotate_down", "Tilt the globe down"),
+    (Action::ToggleHelp, "toggle_help", "Show or hide this help"),
+];
+
+/// Bindings used unless the config overrides them
+const DEFAULT_KEYS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "Q"]),
+    (Action::Back, &["esc"]),
+    (Action::GoHome, &["h"]),
+    (Action::OpenApps, &["a"]),
+    (Action::OpenRecent, &["r"]),
+    (Action::OpenNotes, &["N"]),
+    (Action::OpenProjects, &["P"]),
+    (Action::OpenSettings, &["s"]),
+    (Action::NavigateUp, &["up", "k"]),
+    (Action::NavigateDown, &["down", "j"]),
+    (Action::NavigateLeft, &["left"]),
+    (Action::NavigateRight, &["right"]),
+    (Action::Launch, &["enter", "l"]),
+    (Action::CycleBody, &["b"]),
+    (Action::ZoomIn, &["+", "="]),
+    (Action::ZoomOut, &["-"]),
+    (Action::TogglePause, &["p", "space"]),
+    (Action::ResetView, &["0"]),
+    (Action::RotateLeft, &["shift+left"]),
+    (Action::RotateRight, &["shift+right"]),
+    (Action::RotateUp, &["shift+up"]),
+    (Action::RotateDown, &["shift+down"]),
+    (Action::ToggleHelp, &["?"]),
+];
+
+/// Home view defaults on top of `DEFAULT_KEYS`: `l` is for lists only and
+/// new files are made from home
+const DEFAULT_HOME_KEYS: &[(Action, &[&str])] = &[
+    (Action::Launch, &["enter"]),
+    (Action::NewFile, &["n"]),
+];
+
+/// Recent view defaults on top of `DEFAULT_KEYS`. `p` pins here, so pausing
+/// the globe is left on space.
+const DEFAULT_RECENT_KEYS: &[(Action, &[&str])] = &[
+    (Action::TogglePin, &["p"]),
+    (Action::TogglePause, &["space"]),
+    (Action::RemoveFile, &["d", "delete"]),
+    (Action::ClearRecent, &["D"]),
+    (Action::OpenWith, &["o"]),
+];
+
+/// Notes view defaults on top of `DEFAULT_KEYS`: `n` starts a note here
+const DEFAULT_NOTES_KEYS: &[(Action, &[&str])] = &[
+    (Action::NewFile, &["n"]),
+];
+
+/// Projects view defaults on top of `DEFAULT_KEYS`
+const DEFAULT_PROJECTS_KEYS: &[(Action, &[&str])] = &[
+    (Action::OpenWith, &["o"]),
+];
+
+impl Action {
+    fn from_name(name: &str) -> Result<Self> {
+        ACTIONS
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(action, _, _)| *action)
+            .with_context(|| format!("Unknown action '{}'", name))
+    }
+
+    pub fn description(self) -> &'static str {
+        ACTIONS.iter().find(|(a, _, _)| *a == self).map(|(_, _, d)| *d).unwrap_or("")
+    }
+}
+
+/// A key with its modifiers, e.g. `ctrl+q`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of a character ('Q', '?'), and terminals
+        // disagree on whether they report it
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parse a key such as `q`, `enter`, `ctrl+c` or `shift+left`
+    pub fn parse(text: &str) -> Result<Self> {
+        // The key itself may be '+', as in "+" or "ctrl++"
+        let (modifier_names, name) = if text == "+" {
+            ("", "+")
+        } else if let Some(mods) = text.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_names.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => anyhow::bail!("Unknown modifier '{}' in key '{}'", other, text),
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                lower => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => anyhow::bail!("Unknown key '{}'", text),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl+"),
+            (KeyModifiers::ALT, "alt+"),
+            (KeyModifiers::SHIFT, "shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            code => f.write_str(&format!("{:?}", code).to_lowercase()),
+        }
+    }
+}
+
+/// Keys pressed one after another, e.g. `g g`
+pub type Chord = Vec<Key>;
+
+/// Parse a space-separated chord such as `"g g"` or `"ctrl+x ctrl+c"`
+fn parse_chord(text: &str) -> Result<Chord> {
+    let chord: Chord = text.split_whitespace().map(Key::parse).collect::<Result<_>>()?;
+    if chord.is_empty() {
+        anyhow::bail!("Empty key binding");
+    }
+    Ok(chord)
+}
+
+/// What a sequence of keys means in a view
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolved {
+    Action(Action),
+    /// The keys start a chord; wait for more
+    Pending,
+    None,
+}
+
+/// Key bindings for every view
+pub struct Keymap {
+    views: HashMap<AppState, Vec<(Chord, Action)>>,
+}
+
+impl Keymap {
+    /// Build the keymap from the defaults and the `[keys]` config. An action
+    /// listed in a view's section replaces its global bindings in that
+    /// view; an empty list unbinds it.
+    pub fn new(config: &KeysConfig) -> Result<Self> {
+        let mut global = bindings_from(DEFAULT_KEYS)?;
+        global.extend(parse_section(&config.global).context("Invalid [keys.global]")?);
+
+        let mut views = HashMap::new();
+        for (state, section, name) in [
+            (AppState::Home, &config.home, "home"),
+            (AppState::Apps, &config.apps, "apps"),
+            (AppState::RecentFiles, &config.recent, "recent"),
+            (AppState::Settings, &config.settings, "settings"),
+            (AppState::Notes, &config.notes, "notes"),
+            (AppState::Projects, &config.projects, "projects"),
+        ] {
+            let mut view = global.clone();
+            match state {
+                AppState::Home => view.extend(bindings_from(DEFAULT_HOME_KEYS)?),
+                AppState::RecentFiles => view.extend(bindings_from(DEFAULT_RECENT_KEYS)?),
+                AppState::Notes => view.extend(bindings_from(DEFAULT_NOTES_KEYS)?),
+                AppState::Projects => view.extend(bindings_from(DEFAULT_PROJECTS_KEYS)?),
+                _ => {}
+            }
+            view.extend(parse_section(section).with_context(|| format!("Invalid [keys.{}]", name))?);
+
+            let chords = view
+                .into_iter()
+                .flat_map(|(action, chords)| chords.into_iter().map(move |chord| (chord, action)))
+                .collect();
+            views.insert(state, chords);
+        }
+        Ok(Self { views })
+    }
+
+    /// Look up `keys` pressed in `state`. A complete binding wins over a
+    /// longer chord starting with the same keys.
+    pub fn resolve(&self, state: AppState, keys: &[Key]) -> Resolved {
+        let bindings = self.views.get(&state).map(Vec::as_slice).unwrap_or(&[]);
+        if let Some((_, action)) = bindings.iter().find(|(chord, _)| chord.as_slice() == keys) {
+            Resolved::Action(*action)
+        } else if bindings.iter().any(|(chord, _)| chord.starts_with(keys)) {
+            Resolved::Pending
+        } else {
+            Resolved::None
+        }
+    }
+
+    /// Bound actions in `state` with their keys, in help order
+    pub fn help(&self, state: AppState) -> Vec<(Action, Vec<String>)> {
+        let bindings = self.views.get(&state).map(Vec::as_slice).unwrap_or(&[]);
+        ACTIONS
+            .iter()
+            .filter_map(|(action, _, _)| {
+                let keys: Vec<String> = bindings
+                    .iter()
+                    .filter(|(_, a)| a == action)
+                    .map(|(chord, _)| chord.iter().map(Key::to_string).collect::<Vec<_>>().join(" "))
+                    .collect();
+                (!keys.is_empty()).then_some((*action, keys))
+            })
+            .collect()
+    }
+}
+
+fn bindings_from(defaults: &[(Action, &[&str])]) -> Result<BTreeMap<Action, Vec<Chord>>> {
+    defaults
+        .iter()
+        .map(|(action, keys)| Ok((*action, keys.iter().map(|k| parse_chord(k)).collect::<Result<_>>()?)))
+        .collect()
+}
+
+fn parse_section(section: &BTreeMap<String, Vec<String>>) -> Result<BTreeMap<Action, Vec<Chord>>> {
+    section
+        .iter()
+        .map(|(name, keys)| {
+            let action = Action::from_name(name)?;
+            let chords = keys
+                .iter()
+                .map(|k| parse_chord(k).with_context(|| format!("Invalid key '{}' for {}", k, name)))
+                .collect::<Result<_>>()?;
+            Ok((action, chords))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> Key {
+        Key::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(key("ctrl+c"), Key::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(key("shift+left"), Key::new(KeyCode::Left, KeyModifiers::SHIFT));
+        assert_eq!(key("+"), Key::new(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(key("alt++"), Key::new(KeyCode::Char('+'), KeyModifiers::ALT));
+        assert_eq!(key("F5"), Key::new(KeyCode::F(5), KeyModifiers::NONE));
+        // Shift is implied by the character
+        assert_eq!(key("?"), Key::from(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+        assert!(Key::parse("hyper+x").is_err());
+        assert!(Key::parse("enterr").is_err());
+
+        for text in ["ctrl+alt+x", "space", "enter", "shift+up", "f12", "+"] {
+            assert_eq!(key(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_view_overrides_and_chords() {
+        let mut config = KeysConfig::default();
+        config.global.insert("quit".to_string(), vec!["ctrl+q".to_string()]);
+        config.apps.insert("launch".to_string(), vec!["g g".to_string()]);
+        let keymap = Keymap::new(&config).unwrap();
+
+        // Global override replaces the default
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("q")]), Resolved::None);
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("ctrl+q")]), Resolved::Action(Action::Quit));
+
+        // View override replaces the global binding in that view only
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("enter")]), Resolved::None);
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("g")]), Resolved::Pending);
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("g"), key("g")]), Resolved::Action(Action::Launch));
+        assert_eq!(keymap.resolve(AppState::Settings, &[key("enter")]), Resolved::Action(Action::Launch));
+
+        // Home's own defaults
+        assert_eq!(keymap.resolve(AppState::Home, &[key("l")]), Resolved::None);
+        assert_eq!(keymap.resolve(AppState::Home, &[key("n")]), Resolved::Action(Action::NewFile));
+        assert_eq!(keymap.resolve(AppState::Apps, &[key("n")]), Resolved::None);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut config = KeysConfig::default();
+        config.global.insert("fly".to_string(), vec!["f".to_string()]);
+        assert!(Keymap::new(&config).is_err());
+
+        let mut config = KeysConfig::default();
+        config.home.insert("quit".to_string(), vec!["ctrl+".to_string()]);
+        let err = Keymap::new(&config).err().unwrap();
+        assert!(format!("{:#}", err).contains("keys.home"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_help_lists_bindings() {
+        let keymap = Keymap::new(&KeysConfig::default()).unwrap();
+        let help = keymap.help(AppState::Home);
+        let launch = help.iter().find(|(a, _)| *a == Action::Launch).unwrap();
+        assert_eq!(launch.1, vec!["enter"]);
+        assert!(help.iter().any(|(a, keys)| *a == Action::ToggleHelp && keys == &["?"]));
+    }
+}
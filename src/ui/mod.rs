@@ -1,5 +1,9 @@
 pub mod app;
+pub mod color;
 pub mod components;
+pub mod keymap;
+pub mod new_file;
+pub mod note_editor;
 pub mod theme;
 pub mod views;
 
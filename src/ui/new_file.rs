@@ -0,0 +1,212 @@
+use crate::launcher::template::{self, Template};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Completions listed under the input
+const MAX_MATCHES: usize = 6;
+
+/// The prompt for naming a new file and picking its template. Names are
+/// relative to `base_dir` unless absolute or starting with `~/`.
+pub struct NewFilePrompt {
+    pub input: String,
+    /// An empty file, then every template
+    pub templates: Vec<Template>,
+    pub selected: usize,
+    /// Candidates from the last completion that didn't narrow to one
+    pub matches: Vec<String>,
+    /// Why the last attempt to create the file failed
+    pub error: Option<String>,
+    base_dir: PathBuf,
+}
+
+impl NewFilePrompt {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self::with_templates(base_dir, template::load_templates())
+    }
+
+    /// A prompt offering an empty file followed by `templates`
+    pub fn with_templates(base_dir: PathBuf, templates: Vec<Template>) -> Self {
+        let empty = Template {
+            name: "new_file".to_string(),
+            extension: "txt".to_string(),
+            body: String::new(),
+        };
+        Self {
+            input: String::new(),
+            templates: std::iter::once(empty).chain(templates).collect(),
+            selected: 0,
+            matches: Vec::new(),
+            error: None,
+            base_dir,
+        }
+    }
+
+    /// Name shown for template `index`
+    pub fn template_label(&self, index: usize) -> &str {
+        if index == 0 {
+            "empty"
+        } else {
+            &self.templates[index].name
+        }
+    }
+
+    pub fn template(&self) -> &Template {
+        &self.templates[self.selected]
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.input.push(c);
+        self.matches.clear();
+        self.error = None;
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+        self.matches.clear();
+        self.error = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.matches.clear();
+        self.error = None;
+    }
+
+    pub fn move_template(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.templates.len() - 1);
+    }
+
+    /// Complete the path being typed as far as it's unambiguous
+    pub fn complete(&mut self) {
+        let (completed, matches) = complete_path(&self.input, &self.base_dir);
+        self.input = completed;
+        self.matches = matches;
+    }
+
+    /// Where the file will be created: the typed name, or a generated one,
+    /// with the template's extension when the name has none
+    pub fn target(&self, now: DateTime<Local>) -> PathBuf {
+        let template = self.template();
+        let name = self.input.trim();
+        if name.is_empty() {
+            return self.base_dir.join(template.default_file_name(now));
+        }
+        let mut path = resolve(name, &self.base_dir);
+        if path.extension().is_none() {
+            path.set_extension(&template.extension);
+        }
+        path
+    }
+}
+
+/// `input` as a path: `~/` is the home directory and relative paths are
+/// under `base_dir`
+fn resolve(input: &str, base_dir: &Path) -> PathBuf {
+    match (input.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => base_dir.join(input),
+    }
+}
+
+/// Complete the last component of `input` against the directory it names.
+/// Returns the extended input, a `/` added after a lone directory, and the
+/// matching names when there's more than one.
+pub fn complete_path(input: &str, base_dir: &Path) -> (String, Vec<String>) {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        base_dir.to_path_buf()
+    } else {
+        resolve(dir_part, base_dir)
+    };
+
+    let mut names: Vec<(String, bool)> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| (entry.file_name().to_string_lossy().into_owned(), entry.path().is_dir()))
+        // Hidden files only when asked for
+        .filter(|(name, _)| name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+        .collect();
+    names.sort();
+
+    match names.as_slice() {
+        [] => (input.to_string(), Vec::new()),
+        [(name, is_dir)] => {
+            let suffix = if *is_dir { "/" } else { "" };
+            (format!("{}{}{}", dir_part, name, suffix), Vec::new())
+        }
+        _ => {
+            let common = names[1..].iter().fold(names[0].0.clone(), |common, (name, _)| {
+                common
+                    .chars()
+                    .zip(name.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a)
+                    .collect()
+            });
+            let matches = names
+                .into_iter()
+                .take(MAX_MATCHES)
+                .map(|(name, is_dir)| if is_dir { name + "/" } else { name })
+                .collect();
+            (format!("{}{}", dir_part, common), matches)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_complete_path() {
+        let dir = TempDir::new("complete");
+        fs::create_dir_all(dir.join("projects/velocity")).unwrap();
+        fs::write(dir.join("notes-work.md"), "").unwrap();
+        fs::write(dir.join("notes-home.md"), "").unwrap();
+        fs::write(dir.join(".hidden"), "").unwrap();
+
+        let results = [
+            complete_path("pro", &dir),
+            complete_path("projects/v", &dir),
+            complete_path("no", &dir),
+            complete_path("zzz", &dir),
+            complete_path(".h", &dir),
+        ];
+
+        assert_eq!(results[0], ("projects/".to_string(), vec![]));
+        assert_eq!(results[1], ("projects/velocity/".to_string(), vec![]));
+        assert_eq!(
+            results[2],
+            ("notes-".to_string(), vec!["notes-home.md".to_string(), "notes-work.md".to_string()])
+        );
+        assert_eq!(results[3], ("zzz".to_string(), vec![]));
+        assert_eq!(results[4], (".hidden".to_string(), vec![]));
+    }
+
+    #[test]
+    fn test_target() {
+        let now = chrono::TimeZone::with_ymd_and_hms(&Local, 2024, 3, 4, 5, 6, 7).unwrap();
+        let mut prompt = NewFilePrompt::with_templates(PathBuf::from("/notes"), template::bundled_templates());
+        assert_eq!(prompt.target(now), PathBuf::from("/notes/new_file_20240304_050607.txt"));
+
+        prompt.selected = prompt.templates.iter().position(|t| t.name == "scratch" && t.extension == "rs").unwrap();
+        for c in "work/idea".chars() {
+            prompt.insert(c);
+        }
+        assert_eq!(prompt.target(now), PathBuf::from("/notes/work/idea.rs"));
+        prompt.clear();
+        for c in "/tmp/list.txt".chars() {
+            prompt.insert(c);
+        }
+        assert_eq!(prompt.target(now), PathBuf::from("/tmp/list.txt"));
+    }
+}
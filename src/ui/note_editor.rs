@@ -0,0 +1,275 @@
+use crate::notes;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+// Spaces typed for Tab
+const TAB_WIDTH: usize = 4;
+
+/// A note being edited: its text as lines, with the cursor at a line and a
+/// character within it
+pub struct NoteEditor {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    pub row: usize,
+    pub col: usize,
+    /// First screen row shown, kept by the view so the cursor stays visible
+    pub scroll: usize,
+    /// Changed since it was opened or last saved
+    pub modified: bool,
+    /// The file doesn't exist yet
+    is_new: bool,
+    /// Why the last save failed
+    pub error: Option<String>,
+}
+
+impl NoteEditor {
+    /// An empty note to be saved at `path`
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+            scroll: 0,
+            modified: false,
+            is_new: true,
+            error: None,
+        }
+    }
+
+    /// Edit the note at `path`, with the cursor at the end
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let text = fs::read_to_string(&path).with_context(|| format!("Failed to read note {:?}", path))?;
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Ok(Self {
+            path,
+            lines,
+            row,
+            col,
+            scroll: 0,
+            modified: false,
+            is_new: false,
+            error: None,
+        })
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.trim().is_empty())
+    }
+
+    /// Write the note if it changed. Returns whether anything was written.
+    pub fn save(&mut self) -> Result<bool> {
+        // An untouched new note isn't worth a file
+        if !self.modified || (self.is_new && self.is_empty()) {
+            return Ok(false);
+        }
+        notes::save_note(&self.path, &self.text(), self.is_new)?;
+        self.is_new = false;
+        self.modified = false;
+        self.error = None;
+        Ok(true)
+    }
+
+    /// Byte offset of character `col` in the cursor's line
+    fn byte_at(&self, col: usize) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len())
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    fn edited(&mut self) {
+        self.modified = true;
+        self.error = None;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_at(self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.edited();
+    }
+
+    pub fn insert_tab(&mut self) {
+        for _ in 0..TAB_WIDTH {
+            self.insert(' ');
+        }
+    }
+
+    /// Split the line at the cursor
+    pub fn newline(&mut self) {
+        let at = self.byte_at(self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+        self.edited();
+    }
+
+    /// Delete before the cursor, joining onto the line above at its start
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.byte_at(self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+            self.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.edited();
+    }
+
+    /// Delete under the cursor, pulling up the next line at the end
+    pub fn delete(&mut self) {
+        if self.col < self.line_len(self.row) {
+            let at = self.byte_at(self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return;
+        }
+        self.edited();
+    }
+
+    pub fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if self.col < self.line_len(self.row) {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    /// Move `delta` lines, keeping the column where the line is long enough
+    pub fn move_vertical(&mut self, delta: isize) {
+        self.row = self.row.saturating_add_signed(delta).min(self.lines.len() - 1);
+        self.col = self.col.min(self.line_len(self.row));
+    }
+
+    pub fn move_home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.col = self.line_len(self.row);
+    }
+
+    /// The text wrapped to `width` columns, as screen rows, and the row and
+    /// column the cursor is drawn at
+    pub fn wrapped(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let mut rows = Vec::new();
+        let mut cursor = (0, 0);
+        for (row, line) in self.lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let starts = wrap(&chars, width);
+            if row == self.row {
+                // The cursor goes on the last screen row starting at or before it
+                let i = starts.iter().rposition(|&start| start <= self.col).unwrap_or(0);
+                let x = self.col - starts[i];
+                cursor = if x >= width { (rows.len() + i + 1, 0) } else { (rows.len() + i, x) };
+            }
+            for (i, &start) in starts.iter().enumerate() {
+                let end = starts.get(i + 1).copied().unwrap_or(chars.len());
+                rows.push(chars[start..end].iter().collect());
+            }
+        }
+        (rows, cursor)
+    }
+}
+
+/// Where each screen row of `line` starts when it's wrapped at `width`,
+/// breaking after the last space that fits or mid-word when none does
+pub fn wrap(line: &[char], width: usize) -> Vec<usize> {
+    let width = width.max(1);
+    let mut starts = vec![0];
+    let mut start = 0;
+    while line.len() - start > width {
+        let next = line[start..start + width]
+            .iter()
+            .rposition(|c| *c == ' ')
+            .filter(|&i| i > 0)
+            .map(|i| start + i + 1)
+            .unwrap_or(start + width);
+        starts.push(next);
+        start = next;
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> NoteEditor {
+        let mut editor = NoteEditor::new(PathBuf::from("/notes/test.md"));
+        for c in text.chars() {
+            match c {
+                '\n' => editor.newline(),
+                c => editor.insert(c),
+            }
+        }
+        editor
+    }
+
+    #[test]
+    fn test_editing() {
+        let mut editor = typed("héllo\nworld");
+        assert_eq!(editor.text(), "héllo\nworld\n");
+
+        editor.move_vertical(-1);
+        editor.move_end();
+        editor.delete();
+        assert_eq!(editor.lines, ["hélloworld"]);
+        editor.newline();
+        editor.move_home();
+        editor.backspace();
+        assert_eq!((editor.lines.clone(), editor.row, editor.col), (vec!["hélloworld".to_string()], 0, 5));
+        editor.move_left();
+        editor.backspace();
+        assert_eq!(editor.lines, ["héloworld"]);
+        assert!(editor.modified);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let chars: Vec<char> = "the quick brown fox".chars().collect();
+        assert_eq!(wrap(&chars, 10), [0, 10]);
+        assert_eq!(wrap(&chars, 19), [0]);
+        let word: Vec<char> = "abcdefghij".chars().collect();
+        assert_eq!(wrap(&word, 4), [0, 4, 8]);
+
+        let editor = typed("the quick brown fox\nend");
+        let (rows, cursor) = editor.wrapped(10);
+        assert_eq!(rows, ["the quick ", "brown fox", "end"]);
+        assert_eq!(cursor, (2, 3));
+    }
+}
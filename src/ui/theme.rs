@@ -1,58 +1,218 @@
-use ratatui::style::Color;
-use crate::config::ThemeConfig;
+use ratatui::style::{Color, Modifier, Style};
+use crate::ui::color::ColorSupport;
+use crate::config::{Config, ThemeConfig};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Themes built into the binary, by name
+const BUNDLED_THEMES: &[(&str, &str)] = &[
+    ("default", include_str!("../../themes/default.toml")),
+    ("solarized", include_str!("../../themes/solarized.toml")),
+    ("gruvbox", include_str!("../../themes/gruvbox.toml")),
+    ("high-contrast", include_str!("../../themes/high-contrast.toml")),
+    ("monochrome", include_str!("../../themes/monochrome.toml")),
+];
 
 pub struct Theme {
     config: ThemeConfig,
+    /// What the terminal can show; `None` when `NO_COLOR` asks for no colour
+    support: Option<ColorSupport>,
 }
 
 impl Theme {
-    pub fn new(config: ThemeConfig) -> Self {
-        Self { config }
+    pub fn new(config: ThemeConfig, support: Option<ColorSupport>) -> Self {
+        Self { config, support }
+    }
+
+    /// `rgb` as the closest colour the terminal can show, or the terminal's
+    /// default colour when colour is off
+    fn color(&self, rgb: [u8; 3]) -> Color {
+        match self.support {
+            Some(support) => support.quantize(rgb),
+            None => Color::Reset,
+        }
+    }
+
+    /// Foreground `color`, or `modifier` in its place when colour is off
+    fn style(&self, color: Color, modifier: Modifier) -> Style {
+        match self.support {
+            Some(_) => Style::default().fg(color),
+            None => Style::default().add_modifier(modifier),
+        }
     }
 
     pub fn text_primary(&self) -> Color {
-        Color::Rgb(self.config.text_primary[0], self.config.text_primary[1], self.config.text_primary[2])
+        self.color(self.config.text_primary)
     }
 
     pub fn text_secondary(&self) -> Color {
-        Color::Rgb(self.config.text_secondary[0], self.config.text_secondary[1], self.config.text_secondary[2])
+        self.color(self.config.text_secondary)
     }
 
     pub fn text_selected(&self) -> Color {
-        Color::Rgb(self.config.text_selected[0], self.config.text_selected[1], self.config.text_selected[2])
+        self.color(self.config.text_selected)
     }
 
     pub fn text_accent(&self) -> Color {
-        Color::Rgb(self.config.text_accent[0], self.config.text_accent[1], self.config.text_accent[2])
+        self.color(self.config.text_accent)
     }
 
     pub fn star_color(&self, brightness: u8) -> Color {
         match brightness {
-            0..=1 => Color::Rgb(self.config.star_dim[0], self.config.star_dim[1], self.config.star_dim[2]),
-            2 => Color::Rgb(self.config.star_medium[0], self.config.star_medium[1], self.config.star_medium[2]),
-            3 => Color::Rgb(self.config.star_light[0], self.config.star_light[1], self.config.star_light[2]),
-            4 => Color::Rgb(self.config.star_bright[0], self.config.star_bright[1], self.config.star_bright[2]),
-            _ => Color::Rgb(self.config.star_brightest[0], self.config.star_brightest[1], self.config.star_brightest[2]),
+            0..=1 => self.color(self.config.star_dim),
+            2 => self.color(self.config.star_medium),
+            3 => self.color(self.config.star_light),
+            4 => self.color(self.config.star_bright),
+            _ => self.color(self.config.star_brightest),
         }
     }
 
     pub fn status_good(&self) -> Color {
-        Color::Rgb(self.config.status_good[0], self.config.status_good[1], self.config.status_good[2])
+        self.color(self.config.status_good)
     }
 
     pub fn status_warning(&self) -> Color {
-        Color::Rgb(self.config.status_warning[0], self.config.status_warning[1], self.config.status_warning[2])
+        self.color(self.config.status_warning)
     }
 
     pub fn status_error(&self) -> Color {
-        Color::Rgb(self.config.status_error[0], self.config.status_error[1], self.config.status_error[2])
+        self.color(self.config.status_error)
     }
 
     pub fn status_info(&self) -> Color {
-        Color::Rgb(self.config.status_info[0], self.config.status_info[1], self.config.status_info[2])
+        self.color(self.config.status_info)
     }
 
     pub fn border(&self) -> Color {
-        Color::Rgb(self.config.border[0], self.config.border[1], self.config.border[2])
+        self.color(self.config.border)
+    }
+
+    /// Style for a star of `brightness` 0-5; dim or bold stand in for the
+    /// colours without colour
+    pub fn star_style(&self, brightness: u8) -> Style {
+        let modifier = match brightness {
+            0..=1 => Modifier::DIM,
+            2..=3 => Modifier::empty(),
+            _ => Modifier::BOLD,
+        };
+        self.style(self.star_color(brightness), modifier)
+    }
+
+    /// Style for a gauge at `percent` full: good, warning or error. Without
+    /// colour a warning is bold and an error reversed.
+    pub fn level_style(&self, percent: f64) -> Style {
+        if percent < 50.0 {
+            self.style(self.status_good(), Modifier::empty())
+        } else if percent < 80.0 {
+            self.style(self.status_warning(), Modifier::BOLD)
+        } else {
+            self.style(self.status_error(), Modifier::BOLD | Modifier::REVERSED)
+        }
+    }
+
+    /// One of each kind of colour, for previewing the theme
+    pub fn swatch(&self) -> Vec<Color> {
+        vec![
+            self.text_primary(),
+            self.text_secondary(),
+            self.text_accent(),
+            self.star_color(1),
+            self.star_color(3),
+            self.star_color(5),
+            self.status_good(),
+            self.status_warning(),
+            self.status_error(),
+            self.status_info(),
+        ]
+    }
+}
+
+/// `themes/` in the config dir, where user themes live
+fn themes_dir() -> Option<PathBuf> {
+    Config::config_dir().ok().map(|dir| dir.join("themes"))
+}
+
+/// Bundled theme names followed by any other `*.toml` files in `themes/`
+pub fn theme_names() -> Vec<String> {
+    let mut names: Vec<String> = BUNDLED_THEMES.iter().map(|(name, _)| name.to_string()).collect();
+    let mut user: Vec<String> = themes_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .filter(|name| !names.contains(name))
+        .collect();
+    user.sort();
+    names.append(&mut user);
+    names
+}
+
+/// Load theme `name`. A file in `themes/` takes precedence over the bundled
+/// theme of the same name.
+pub fn load_theme(name: &str) -> Result<ThemeConfig> {
+    if let Some(path) = themes_dir().map(|dir| dir.join(format!("{}.toml", name))) {
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read theme {:?}", path))?;
+            return parse_theme(&content).with_context(|| format!("Invalid theme {:?}", path));
+        }
+    }
+    let (_, content) = BUNDLED_THEMES
+        .iter()
+        .find(|(bundled, _)| *bundled == name)
+        .with_context(|| format!("Unknown theme '{}'", name))?;
+    parse_theme(content).with_context(|| format!("Invalid bundled theme '{}'", name))
+}
+
+/// Parse a theme file. Colours missing from it keep their defaults.
+fn parse_theme(content: &str) -> Result<ThemeConfig> {
+    Ok(toml::from_str(content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_follow_terminal_support() {
+        let config = parse_theme("text_accent = \"#ff0000\"").unwrap();
+        let truecolor = Theme::new(config.clone(), Some(ColorSupport::TrueColor));
+        assert_eq!(truecolor.text_accent(), Color::Rgb(255, 0, 0));
+        let ansi256 = Theme::new(config.clone(), Some(ColorSupport::Ansi256));
+        assert_eq!(ansi256.text_accent(), Color::Indexed(196));
+        let ansi16 = Theme::new(config.clone(), Some(ColorSupport::Ansi16));
+        assert_eq!(ansi16.text_accent(), Color::LightRed);
+
+        let plain = Theme::new(config, None);
+        assert_eq!(plain.text_accent(), Color::Reset);
+        assert_eq!(plain.star_style(5), Style::default().add_modifier(Modifier::BOLD));
+        assert_eq!(plain.level_style(90.0).fg, None);
+        assert!(plain.level_style(90.0).add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_bundled_themes_parse() {
+        for (name, content) in BUNDLED_THEMES {
+            parse_theme(content).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        }
+        let solarized = parse_theme(BUNDLED_THEMES[1].1).unwrap();
+        assert_eq!(solarized.text_accent, [0x26, 0x8b, 0xd2]);
+    }
+
+    #[test]
+    fn test_parse_theme_accepts_hex_and_arrays() {
+        let theme = parse_theme("text_primary = \"#1e1e2e\"\nborder = [1, 2, 3]\nstar_dim = \"FFFFFF\"\n").unwrap();
+        assert_eq!(theme.text_primary, [0x1e, 0x1e, 0x2e]);
+        assert_eq!(theme.border, [1, 2, 3]);
+        assert_eq!(theme.star_dim, [255, 255, 255]);
+        // Unset colours keep their defaults
+        assert_eq!(theme.text_accent, [100, 150, 255]);
+
+        assert!(parse_theme("border = \"#12345\"").is_err());
+        assert!(parse_theme("border = \"#gggggg\"").is_err());
     }
 }
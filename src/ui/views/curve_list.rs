@@ -0,0 +1,90 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Modifier};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use std::collections::HashSet;
+use crate::ui::components::GlobeComponent;
+use crate::ui::components::{calculate_curve_positions, slot_item, CURSOR_SLOT, NightSky};
+use crate::ui::Theme;
+
+/// Draw the globe on the left half of the screen with `labels` curving
+/// round its right edge, item `selected` at the cursor slot and the rest
+/// scrolling round it, over a starfield. Returns each drawn item's index
+/// and the cells its label covers.
+pub fn render_curve_list(frame: &mut Frame, globe: &mut GlobeComponent, labels: &[String], selected: usize, hovered: Option<usize>, stars: &mut NightSky, theme: &Theme) -> Vec<(usize, Rect)> {
+    let area = frame.size();
+
+    // Split: 50% globe (left), 50% content (right) - matching home view exactly
+    let chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let globe_area = chunks[0];
+    let globe_width = globe_area.width as usize;
+    let globe_height = globe_area.height as usize;
+    let globe_frame = globe.render(globe_width, globe_height).ok();
+
+    // The cursor stays at CURSOR_SLOT, and items scroll around it
+    let selected = selected.min(labels.len().saturating_sub(1));
+    let items: Vec<(usize, String, Rect)> = calculate_curve_positions(area)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, (x, y))| x < area.width && y < area.height)
+        .filter_map(|(slot_index, (x, y))| {
+            let index = slot_item(slot_index, selected, labels.len())?;
+            let text = if slot_index == CURSOR_SLOT {
+                format!("{} <", labels[index])
+            } else {
+                labels[index].clone()
+            };
+            let width = (text.chars().count() as u16).min(area.width - x);
+            Some((index, text, Rect { x, y, width, height: 1 }))
+        })
+        .collect();
+
+    // Stars go behind everything, so skip cells the globe and labels cover
+    let mut occupied_positions = HashSet::new();
+    for (y, row) in globe_frame.iter().flat_map(|rows| rows.iter()).take(globe_height).enumerate() {
+        for (x, cell) in row.iter().take(globe_width).enumerate() {
+            if !cell.is_blank() {
+                occupied_positions.insert((globe_area.x + x as u16, globe_area.y + y as u16));
+            }
+        }
+    }
+    for (_, _, rect) in &items {
+        occupied_positions.extend((rect.x..rect.right()).map(|x| (x, rect.y)));
+    }
+    stars.render_with_occupied_positions(frame, area, &occupied_positions, theme);
+
+    // Only write non-space globe cells to preserve stars in empty spaces
+    for (y, row) in globe_frame.iter().flat_map(|rows| rows.iter()).take(globe_height).enumerate() {
+        for (x, cell) in row.iter().take(globe_width).enumerate() {
+            if !cell.is_blank() {
+                let abs_x = globe_area.x + x as u16;
+                let abs_y = globe_area.y + y as u16;
+                frame.buffer_mut().get_mut(abs_x, abs_y).set_char(cell.ch).set_style(cell.style);
+            }
+        }
+    }
+
+    items
+        .into_iter()
+        .map(|(index, text, rect)| {
+            let style = if index == selected {
+                Style::default()
+                    .fg(theme.text_selected())
+                    .add_modifier(Modifier::BOLD)
+            } else if hovered == Some(index) {
+                // Under the mouse pointer
+                Style::default()
+                    .fg(theme.text_accent())
+                    .add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(theme.text_primary())
+            };
+            frame.render_widget(Paragraph::new(Line::from(Span::styled(text, style))), rect);
+            (index, rect)
+        })
+        .collect()
+}
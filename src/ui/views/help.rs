@@ -0,0 +1,55 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use crate::ui::keymap::Keymap;
+use crate::ui::{AppState, Theme};
+
+/// Overlay listing the key bindings active in `state`
+pub fn render_help(frame: &mut Frame, keymap: &Keymap, state: AppState, theme: &Theme) {
+    let area = frame.size();
+    let bindings: Vec<(String, &str)> = keymap
+        .help(state)
+        .into_iter()
+        .map(|(action, keys)| (keys.join(", "), action.description()))
+        .collect();
+
+    let keys_width = bindings.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+    let text_width = bindings
+        .iter()
+        .map(|(_, description)| keys_width + 2 + description.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    // Centred box just big enough for the list, clipped to the screen
+    let width = (text_width as u16 + 4).min(area.width);
+    let height = (bindings.len() as u16 + 2).min(area.height);
+    let help_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let lines: Vec<Line> = bindings
+        .into_iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<width$}  ", keys, width = keys_width),
+                    Style::default().fg(theme.text_accent()).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(description, Style::default().fg(theme.text_primary())),
+            ])
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Keys ")
+        .style(Style::default().fg(theme.border()));
+
+    frame.render_widget(Clear, help_area);
+    frame.render_widget(Paragraph::new(lines).block(block), help_area);
+}
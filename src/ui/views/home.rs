@@ -22,28 +22,21 @@ pub fn render_home(frame: &mut Frame, globe: &mut GlobeComponent, stats: &mut Sy
     let globe_width = globe_area.width as usize;
     let globe_height = globe_area.height as usize;
     
-    // Temporarily increase scale to make globe slightly bigger (1.2x multiplier)
-    let original_scale = globe.get_scale();
-    globe.set_scale(original_scale * 1.2);
-    
     if let Ok(globe_frame) = globe.render(globe_width, globe_height) {
         for (y, row) in globe_frame.iter().enumerate() {
             if y >= globe_height {
                 break;
             }
-            let line: String = row.iter().take(globe_width).collect();
-            frame.buffer_mut().set_string(
-                globe_area.x,
-                globe_area.y + y as u16,
-                &line,
-                Style::default(),
-            );
+            for (x, cell) in row.iter().take(globe_width).enumerate() {
+                frame
+                    .buffer_mut()
+                    .get_mut(globe_area.x + x as u16, globe_area.y + y as u16)
+                    .set_char(cell.ch)
+                    .set_style(cell.style);
+            }
         }
     }
     
-    // Restore original scale
-    globe.set_scale(original_scale);
-    
     // Render right side content
     let content_area = chunks[1];
     
@@ -158,16 +151,8 @@ pub fn render_home(frame: &mut Frame, globe: &mut GlobeComponent, stats: &mut Sy
         "N/A".to_string()
     };
     
-    // Helper to get sparkline color based on percentage
-    let get_sparkline_color = |percent: f64| -> Color {
-        if percent < 50.0 {
-            theme.status_good()
-        } else if percent < 80.0 {
-            theme.status_warning()
-        } else {
-            theme.status_error()
-        }
-    };
+    // Helper to get sparkline style based on percentage
+    let get_sparkline_style = |percent: f64| -> Style { theme.level_style(percent) };
     
     // Render stats block
     let stats_block = Block::default()
@@ -263,11 +248,11 @@ pub fn render_home(frame: &mut Frame, globe: &mut GlobeComponent, stats: &mut Sy
             };
             
             if !used_data.is_empty() {
-                let used_color = get_sparkline_color(mem_percent);
+                let used_style = get_sparkline_style(mem_percent);
                 let used_sparkline = Sparkline::default()
                     .data(&used_data)
                     .max(max_value)
-                    .style(Style::default().fg(used_color));
+                    .style(used_style);
                 frame.render_widget(used_sparkline, used_area);
             }
             
@@ -290,11 +275,11 @@ pub fn render_home(frame: &mut Frame, globe: &mut GlobeComponent, stats: &mut Sy
         } else {
             // Single combined sparkline if not enough height (show used memory)
             if !used_data.is_empty() {
-                let used_color = get_sparkline_color(mem_percent);
+                let used_style = get_sparkline_style(mem_percent);
                 let used_sparkline = Sparkline::default()
                     .data(&used_data)
                     .max(max_value)
-                    .style(Style::default().fg(used_color));
+                    .style(used_style);
                 frame.render_widget(used_sparkline, mem_sparkline_area);
             }
         }
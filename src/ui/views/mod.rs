@@ -1,9 +1,25 @@
+pub mod help;
 pub mod home;
 pub mod apps;
+pub mod curve_list;
+pub mod new_file;
+pub mod note_editor;
+pub mod notes;
+pub mod notice;
+pub mod open_with;
+pub mod projects;
 pub mod recent;
 pub mod settings;
 
+pub use help::render_help;
 pub use home::render_home;
 pub use apps::render_apps;
-pub use recent::render_recent;
-pub use settings::render_settings;
+pub use curve_list::render_curve_list;
+pub use new_file::render_new_file;
+pub use note_editor::render_note_editor;
+pub use notes::render_notes;
+pub use notice::render_notice;
+pub use open_with::{render_choices, render_open_with};
+pub use projects::{render_project_menu, render_projects};
+pub use recent::{render_recent, render_recent_preview};
+pub use settings::{render_settings, settings_items, BODY_SETTING, THEME_SETTING};
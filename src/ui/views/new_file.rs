@@ -0,0 +1,63 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use crate::ui::new_file::NewFilePrompt;
+use crate::ui::Theme;
+
+// Width of the prompt box, including its border
+const PROMPT_WIDTH: u16 = 60;
+
+/// Overlay for naming a new file and choosing its template
+pub fn render_new_file(frame: &mut Frame, prompt: &NewFilePrompt, theme: &Theme) {
+    let area = frame.size();
+    let primary = Style::default().fg(theme.text_primary());
+    let secondary = Style::default().fg(theme.text_secondary());
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(" Name: ", secondary),
+        Span::styled(prompt.input.clone(), primary),
+        Span::styled("_", Style::default().fg(theme.text_accent()).add_modifier(Modifier::SLOW_BLINK)),
+    ])];
+    for name in &prompt.matches {
+        lines.push(Line::from(Span::styled(format!("       {}", name), secondary)));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(" Template:", secondary)));
+    for index in 0..prompt.templates.len() {
+        let template = &prompt.templates[index];
+        let label = format!("{} (.{})", prompt.template_label(index), template.extension);
+        lines.push(if index == prompt.selected {
+            Line::from(Span::styled(
+                format!("   {} <", label),
+                Style::default().fg(theme.text_selected()).add_modifier(Modifier::BOLD),
+            ))
+        } else {
+            Line::from(Span::styled(format!("   {}", label), primary))
+        });
+    }
+    lines.push(Line::default());
+    match &prompt.error {
+        Some(error) => lines.push(Line::from(Span::styled(format!(" {}", error), Style::default().fg(theme.status_error())))),
+        None => lines.push(Line::from(Span::styled(" Tab completes, Up/Down picks a template", secondary))),
+    }
+
+    // Centred box, clipped to the screen
+    let width = PROMPT_WIDTH.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let prompt_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" New file ")
+        .style(Style::default().fg(theme.border()));
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(Paragraph::new(lines).block(block), prompt_area);
+}
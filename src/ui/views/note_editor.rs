@@ -0,0 +1,61 @@
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use crate::ui::note_editor::NoteEditor;
+use crate::ui::Theme;
+
+/// The note editor over most of the screen, with the text wrapped to the
+/// box and scrolled to keep the cursor in view
+pub fn render_note_editor(frame: &mut Frame, editor: &mut NoteEditor, theme: &Theme) {
+    let area = frame.size();
+    let width = (area.width - area.width / 5).max(area.width.min(40));
+    let height = (area.height - area.height / 5).max(area.height.min(8));
+    let editor_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    // Inside the border, less the status line
+    let text_width = width.saturating_sub(2).max(1) as usize;
+    let text_height = height.saturating_sub(3).max(1) as usize;
+
+    let (rows, (cursor_row, cursor_col)) = editor.wrapped(text_width);
+    if cursor_row < editor.scroll {
+        editor.scroll = cursor_row;
+    } else if cursor_row >= editor.scroll + text_height {
+        editor.scroll = cursor_row + 1 - text_height;
+    }
+
+    let primary = Style::default().fg(theme.text_primary());
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .skip(editor.scroll)
+        .take(text_height)
+        .map(|row| Line::from(Span::styled(row, primary)))
+        .collect();
+    lines.resize(text_height, Line::default());
+    lines.push(match &editor.error {
+        Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(theme.status_error()))),
+        None => Line::from(Span::styled(
+            "Esc saves and closes, Ctrl+S saves",
+            Style::default().fg(theme.text_secondary()),
+        )),
+    });
+
+    let name = editor.path.file_name().and_then(|n| n.to_str()).unwrap_or("note");
+    let title = format!(" {}{} ", name, if editor.modified { " +" } else { "" });
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .style(Style::default().fg(theme.border()));
+
+    frame.render_widget(Clear, editor_area);
+    frame.render_widget(Paragraph::new(lines).block(block), editor_area);
+    frame.set_cursor(
+        editor_area.x + 1 + cursor_col as u16,
+        editor_area.y + 1 + (cursor_row - editor.scroll) as u16,
+    );
+}
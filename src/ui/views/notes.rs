@@ -0,0 +1,11 @@
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use crate::notes::Note;
+use crate::ui::components::{GlobeComponent, NightSky};
+use crate::ui::views::render_curve_list;
+use crate::ui::Theme;
+
+pub fn render_notes(frame: &mut Frame, globe: &mut GlobeComponent, notes: &[Note], selected_index: usize, hovered: Option<usize>, stars: &mut NightSky, theme: &Theme) -> Vec<(usize, Rect)> {
+    let labels: Vec<String> = notes.iter().map(|note| note.title.clone()).collect();
+    render_curve_list(frame, globe, &labels, selected_index, hovered, stars, theme)
+}
@@ -0,0 +1,26 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+use crate::ui::Theme;
+
+/// One-line warning along the bottom of the screen
+pub fn render_notice(frame: &mut Frame, message: &str, theme: &Theme) {
+    let area = frame.size();
+    if area.height == 0 {
+        return;
+    }
+    let notice_area = Rect {
+        x: area.x,
+        y: area.y + area.height - 1,
+        width: area.width,
+        height: 1,
+    };
+    let line = Line::from(Span::styled(
+        format!(" ! {}", message),
+        Style::default().fg(theme.status_warning()).add_modifier(Modifier::BOLD),
+    ));
+    frame.render_widget(Clear, notice_area);
+    frame.render_widget(Paragraph::new(line), notice_area);
+}
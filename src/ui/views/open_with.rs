@@ -0,0 +1,68 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use crate::ui::app::OpenWithMenu;
+use crate::ui::Theme;
+
+/// Overlay listing the ways to open a recent file
+pub fn render_open_with(frame: &mut Frame, menu: &OpenWithMenu, theme: &Theme) {
+    let names: Vec<String> = menu
+        .choices
+        .iter()
+        .map(|choice| match choice {
+            Some(opener) => opener.name.clone(),
+            None => "System default".to_string(),
+        })
+        .collect();
+    let title = format!(
+        " Open {} with ",
+        menu.path.file_name().and_then(|n| n.to_str()).unwrap_or("file")
+    );
+    render_choices(frame, &title, names, menu.selected, theme);
+}
+
+/// Centred overlay titled `title` listing `names` with `selected` marked
+pub fn render_choices(frame: &mut Frame, title: &str, names: Vec<String>, selected: usize, theme: &Theme) {
+    let area = frame.size();
+    let text_width = names
+        .iter()
+        .map(|name| name.chars().count() + 4)
+        .chain([title.chars().count()])
+        .max()
+        .unwrap_or(0);
+
+    // Centred box just big enough for the list, clipped to the screen
+    let width = (text_width as u16 + 4).min(area.width);
+    let height = (names.len() as u16 + 2).min(area.height);
+    let menu_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let lines: Vec<Line> = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            if i == selected {
+                Line::from(Span::styled(
+                    format!(" {} <", name),
+                    Style::default().fg(theme.text_selected()).add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(Span::styled(format!(" {}", name), Style::default().fg(theme.text_primary())))
+            }
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .style(Style::default().fg(theme.border()));
+
+    frame.render_widget(Clear, menu_area);
+    frame.render_widget(Paragraph::new(lines).block(block), menu_area);
+}
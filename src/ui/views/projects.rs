@@ -0,0 +1,29 @@
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use crate::config::ProjectCommand;
+use crate::projects::Project;
+use crate::ui::app::ProjectMenu;
+use crate::ui::components::{GlobeComponent, NightSky};
+use crate::ui::views::{render_choices, render_curve_list};
+use crate::ui::Theme;
+
+/// Project name with its branch, marked `*` when there are changes
+fn project_label(project: &Project) -> String {
+    let dirty = if project.dirty { "*" } else { "" };
+    match &project.branch {
+        Some(branch) => format!("{} [{}{}]", project.name, branch, dirty),
+        None => format!("{}{}", project.name, dirty),
+    }
+}
+
+pub fn render_projects(frame: &mut Frame, globe: &mut GlobeComponent, projects: &[Project], selected_index: usize, hovered: Option<usize>, stars: &mut NightSky, theme: &Theme) -> Vec<(usize, Rect)> {
+    let labels: Vec<String> = projects.iter().map(project_label).collect();
+    render_curve_list(frame, globe, &labels, selected_index, hovered, stars, theme)
+}
+
+/// Overlay listing the commands that can be run in a project
+pub fn render_project_menu(frame: &mut Frame, menu: &ProjectMenu, commands: &[ProjectCommand], theme: &Theme) {
+    let names = commands.iter().map(|command| command.name.clone()).collect();
+    let title = format!(" Run in {} ", menu.project.name);
+    render_choices(frame, &title, names, menu.selected, theme);
+}
@@ -1,206 +1,60 @@
-use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Style, Modifier};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::layout::Rect;
 use ratatui::Frame;
 use crate::ui::components::GlobeComponent;
-use crate::ui::components::{calculate_curve_positions, CURSOR_SLOT, NightSky};
+use crate::ui::components::{calculate_curve_positions, FilePreview, NightSky};
+use crate::ui::views::render_curve_list;
 use crate::ui::Theme;
-use crate::recent_files::RecentFiles;
+use crate::recent_files::{RecentFileEntry, RecentFiles};
 
-pub fn render_recent(frame: &mut Frame, globe: &mut GlobeComponent, recent_files: &RecentFiles, selected_index: Option<usize>, mut stars: Option<&mut NightSky>, theme: &Theme) {
-    let area = frame.size();
-    
-    // Split: 50% globe (left), 50% content (right) - matching home view exactly
-    let chunks = Layout::default()
-        .direction(ratatui::layout::Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
-    
-    // Render globe on left (chunks[0]) - identical to home view
-    let globe_area = chunks[0];
-    let globe_width = globe_area.width as usize;
-    let globe_height = globe_area.height as usize;
-    
-    // Temporarily increase scale to make globe slightly bigger (1.2x multiplier) - same as home view
-    let original_scale = globe.get_scale();
-    globe.set_scale(original_scale * 1.2);
-    
-    // Pre-render globe to get character buffer and identify occupied positions
-    let mut occupied_positions = std::collections::HashSet::new();
-    
-    if let Ok(globe_frame) = globe.render(globe_width, globe_height) {
-        // Track positions where globe has non-space characters
-        for (y, row) in globe_frame.iter().enumerate() {
-            if y >= globe_height {
-                break;
-            }
-            for (x, &ch) in row.iter().take(globe_width).enumerate() {
-                if ch != ' ' {
-                    let abs_x = globe_area.x + x as u16;
-                    let abs_y = globe_area.y + y as u16;
-                    occupied_positions.insert((abs_x, abs_y));
-                }
-            }
-        }
-    }
-    
-    // Restore original scale
-    globe.set_scale(original_scale);
-    
-    // Pre-calculate fixed positions along the right curve of the globe
-    let positions = calculate_curve_positions(area);
-    
-    // Track file text positions
-    let files = recent_files.get_files().unwrap_or_default();
-    let total_files = files.len();
-    
-    // Use selected_index or default to 0
-    let selected_idx = selected_index.unwrap_or(0).min(total_files.saturating_sub(1));
-    
-    if total_files > 0 {
-        for (slot_index, &(x, y)) in positions.iter().enumerate() {
-            // Calculate which file index should appear at this slot
-            let file_index = if slot_index == CURSOR_SLOT {
-                selected_idx
-            } else if slot_index < CURSOR_SLOT {
-                let offset = CURSOR_SLOT - slot_index;
-                selected_idx.saturating_sub(offset)
-            } else {
-                let offset = slot_index - CURSOR_SLOT;
-                selected_idx + offset
-            };
-            
-            // Bounds checking
-            if file_index >= total_files || y >= area.height || x >= area.width {
-                continue;
-            }
-            
-            let path = &files[file_index];
-            let display_name: String = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| path.to_string_lossy().to_string());
-            
-            let display_text = if slot_index == CURSOR_SLOT {
-                format!("{} <", display_name)
-            } else {
-                display_name.clone()
-            };
-            
-            // Add all positions where file text will be rendered
-            let text_length = display_text.chars().count() as u16;
-            for offset_x in 0..text_length.min(area.width.saturating_sub(x)) {
-                occupied_positions.insert((x + offset_x, y));
-            }
-        }
-    }
-    
-    // Render stars FIRST as background layer, skipping occupied positions
-    if let Some(ref mut stars) = stars {
-        // Force reinitialize stars to ensure they use full screen dimensions
-        // This handles the case where stars were initialized with old dimensions
-        if area.width != stars.initialized_width || area.height != stars.initialized_height {
-            stars.resize(area.width, area.height);
-        }
-        stars.render_with_occupied_positions(frame, area, &occupied_positions, theme);
+/// File name, marked `*` when pinned and followed by any tags
+fn entry_label(entry: &RecentFileEntry) -> String {
+    let path = &entry.path;
+    let mut name: String = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+    if entry.pinned {
+        name = format!("* {}", name);
     }
-    
-    // Now render globe normally (only write non-space characters to preserve stars in empty spaces)
-    globe.set_scale(original_scale * 1.2);
-    
-    if let Ok(globe_frame) = globe.render(globe_width, globe_height) {
-        for (y, row) in globe_frame.iter().enumerate() {
-            if y >= globe_height {
-                break;
-            }
-            for (x, &ch) in row.iter().take(globe_width).enumerate() {
-                // Only write non-space characters to preserve stars in empty spaces
-                if ch != ' ' {
-                    let abs_x = globe_area.x + x as u16;
-                    let abs_y = globe_area.y + y as u16;
-                    frame.buffer_mut().get_mut(abs_x, abs_y).set_char(ch);
-                }
-            }
-        }
+    for tag in &entry.tags {
+        name.push_str(&format!(" #{}", tag));
     }
-    
-    // Restore original scale
-    globe.set_scale(original_scale);
-    
-    // Render recent files list using fixed positions
-    let files = recent_files.get_files().unwrap_or_default();
-    let total_files = files.len();
-    
-    if total_files == 0 {
+    name
+}
+
+// Narrowest preview pane worth drawing
+const MIN_PREVIEW_WIDTH: u16 = 32;
+
+pub fn render_recent(frame: &mut Frame, globe: &mut GlobeComponent, recent_files: &RecentFiles, selected_index: Option<usize>, hovered: Option<usize>, stars: &mut NightSky, theme: &Theme) -> Vec<(usize, Rect)> {
+    let labels: Vec<String> = recent_files.entries().unwrap_or_default().iter().map(entry_label).collect();
+    render_curve_list(frame, globe, &labels, selected_index.unwrap_or(0), hovered, stars, theme)
+}
+
+/// Preview of the selected file, to the right of the longest label so the
+/// pane doesn't shift as the list scrolls. Skipped when there's no room.
+pub fn render_recent_preview(frame: &mut Frame, recent_files: &RecentFiles, selected_index: Option<usize>, preview: &mut FilePreview, theme: &Theme) {
+    let area = frame.size();
+    let files = recent_files.entries().unwrap_or_default();
+    if files.is_empty() {
         return;
     }
-    
-    // Calculate which file appears at each slot based on fixed cursor position
-    // The cursor stays at CURSOR_SLOT, and files scroll around it
-    for (slot_index, &(x, y)) in positions.iter().enumerate() {
-        // Calculate which file index should appear at this slot
-        let file_index = if slot_index == CURSOR_SLOT {
-            // Selected file always appears at cursor position
-            selected_idx
-        } else if slot_index < CURSOR_SLOT {
-            // Files above cursor: selected_idx - offset
-            let offset = CURSOR_SLOT - slot_index;
-            selected_idx.saturating_sub(offset)
-        } else {
-            // Files below cursor: selected_idx + offset
-            let offset = slot_index - CURSOR_SLOT;
-            selected_idx + offset
-        };
-        
-        // Bounds checking - skip if file_index is invalid
-        if file_index >= total_files || y >= area.height || x >= area.width {
-            continue;
-        }
-        
-        let path = &files[file_index];
-        let display_name: String = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| path.to_string_lossy().to_string());
-        
-        // Only the cursor slot shows the selected indicator
-        let is_selected = slot_index == CURSOR_SLOT;
-        
-        // Render the file name at fixed position
-        let display_text = if is_selected {
-            format!("{} <", display_name)
-        } else {
-            display_name.clone()
-        };
-        
-        let style = if is_selected {
-            Style::default()
-                .fg(theme.text_selected())
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(theme.text_primary())
-        };
-        
-        // Calculate available width for this item
-        let available_width = area.width.saturating_sub(x);
-        if available_width == 0 {
-            continue;
-        }
-        
-        // Render the text
-        let line = Line::from(Span::styled(display_text, style));
-        let widget = Paragraph::new(line);
-        frame.render_widget(
-            widget,
-            Rect {
-                x,
-                y,
-                width: available_width,
-                height: 1,
-            },
-        );
+    let selected_idx = selected_index.unwrap_or(0).min(files.len() - 1);
+
+    let positions = calculate_curve_positions(area);
+    let labels_right = positions.iter().map(|&(x, _)| x).max().unwrap_or(0)
+        + files.iter().map(|f| entry_label(f).chars().count() as u16).max().unwrap_or(0)
+        + 2;
+    let preview_x = labels_right + 2;
+    let preview_width = area.width.saturating_sub(preview_x);
+    if preview_width < MIN_PREVIEW_WIDTH || area.height < 6 {
+        return;
     }
+    let preview_area = Rect {
+        x: preview_x,
+        y: area.y + 1,
+        width: preview_width - 1,
+        height: area.height - 2,
+    };
+    preview.render(frame, preview_area, &files[selected_idx].path, theme);
 }
@@ -0,0 +1,10 @@
+#!/usr/bin/env python3
+"""{{filename}}, scratch file from {{date}}"""
+
+
+def main():
+    pass
+
+
+if __name__ == "__main__":
+    main()
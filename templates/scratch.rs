@@ -0,0 +1,5 @@
+// {{filename}}, scratch file from {{date}}
+
+fn main() {
+
+}